@@ -0,0 +1,107 @@
+use tower_lsp::lsp_types::{Position, TextDocumentContentChangeEvent, Url};
+
+/// An open document tracked by a `Workspace`: its URI and current text.
+/// Kept as a plain owned `String` (rather than behind an internal lock)
+/// since `Workspace` only ever hands out `File`s through `DashMap`'s own
+/// per-shard locking.
+#[derive(Debug, Clone)]
+pub struct File {
+    pub url: Url,
+    text: String,
+}
+
+impl File {
+    pub fn new(url: Url, text: String) -> File {
+        File { url, text }
+    }
+
+    pub fn get_content(&self) -> String {
+        self.text.clone()
+    }
+
+    /// Converts an LSP `Position` (UTF-16 line/column) to a byte offset into
+    /// the current content, the inverse of `LineIndex::position`. Positions
+    /// past the end of the text clamp to the end.
+    pub fn get_offset_at(&self, position: Position) -> usize {
+        let mut offset = 0;
+        for (i, line) in self.text.split('\n').enumerate() {
+            if i as u32 != position.line {
+                offset += line.len() + 1;
+                continue;
+            }
+
+            let mut units = 0u32;
+            for (byte_index, ch) in line.char_indices() {
+                if units >= position.character {
+                    return offset + byte_index;
+                }
+                units += ch.len_utf16() as u32;
+            }
+            return offset + line.len();
+        }
+
+        self.text.len()
+    }
+
+    /// Applies one content change in place. A `None` range means the client
+    /// sent the whole document rather than an incremental edit.
+    pub fn apply_change(&mut self, change: TextDocumentContentChangeEvent) {
+        match change.range {
+            None => self.text = change.text,
+            Some(range) => {
+                let start = self.get_offset_at(range.start);
+                let end = self.get_offset_at(range.end);
+                self.text.replace_range(start..end, &change.text);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(text: &str) -> File {
+        File::new(Url::parse("file:///a").unwrap(), text.to_string())
+    }
+
+    #[test]
+    fn it_finds_the_offset_on_the_first_line() {
+        assert_eq!(file("abc\ndef").get_offset_at(Position::new(0, 2)), 2);
+    }
+
+    #[test]
+    fn it_finds_the_offset_on_a_later_line() {
+        assert_eq!(file("abc\ndef\nghi").get_offset_at(Position::new(2, 1)), 9);
+    }
+
+    #[test]
+    fn it_counts_multibyte_characters_as_utf16_units() {
+        assert_eq!(file("héllo\nwörld").get_offset_at(Position::new(1, 3)), 11);
+    }
+
+    #[test]
+    fn it_applies_a_full_document_replace_when_range_is_none() {
+        let mut file = file("old content");
+        file.apply_change(TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "new content".to_string(),
+        });
+        assert_eq!(file.get_content(), "new content");
+    }
+
+    #[test]
+    fn it_applies_a_ranged_edit_in_place() {
+        let mut file = file("a/b/c");
+        file.apply_change(TextDocumentContentChangeEvent {
+            range: Some(tower_lsp::lsp_types::Range::new(
+                Position::new(0, 2),
+                Position::new(0, 3),
+            )),
+            range_length: None,
+            text: "X".to_string(),
+        });
+        assert_eq!(file.get_content(), "a/X/c");
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// An in-memory snapshot of a directory on disk, used to resolve path
+/// completions without touching the filesystem on every keystroke.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Dir { children: HashMap<String, Node> },
+    File { size: u64 },
+}
+
+impl Node {
+    /// Reads a single directory level from disk. Does not recurse into
+    /// child directories; those are read lazily when they are themselves
+    /// resolved.
+    pub fn read_dir(path: &Path) -> Option<Node> {
+        let entries = fs::read_dir(path).ok()?;
+
+        let mut children = HashMap::new();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+
+            let node = if metadata.is_dir() {
+                Node::Dir {
+                    children: HashMap::new(),
+                }
+            } else {
+                Node::File {
+                    size: metadata.len(),
+                }
+            };
+
+            children.insert(name, node);
+        }
+
+        Some(Node::Dir { children })
+    }
+
+    /// Recursively reads the full directory tree rooted at `path`, unlike
+    /// `read_dir` which only reads a single level. Used by the pattern
+    /// matcher, which needs to walk every descendant to decide which paths
+    /// a `.gitignore`'s rules affect, rather than lazily resolve one
+    /// directory at a time.
+    pub fn read_tree(path: &Path) -> Option<Node> {
+        let entries = fs::read_dir(path).ok()?;
+
+        let mut children = HashMap::new();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+
+            let node = if metadata.is_dir() {
+                Node::read_tree(&entry.path()).unwrap_or(Node::Dir {
+                    children: HashMap::new(),
+                })
+            } else {
+                Node::File {
+                    size: metadata.len(),
+                }
+            };
+
+            children.insert(name, node);
+        }
+
+        Some(Node::Dir { children })
+    }
+
+    pub fn children(&self) -> Option<&HashMap<String, Node>> {
+        match self {
+            Node::Dir { children } => Some(children),
+            Node::File { .. } => None,
+        }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        matches!(self, Node::Dir { .. })
+    }
+}
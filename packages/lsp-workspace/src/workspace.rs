@@ -1,13 +1,16 @@
 use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
 
 use dashmap::DashMap;
 use tower_lsp::lsp_types::TextDocumentContentChangeEvent;
 use tower_lsp::lsp_types::Url;
 
 use super::file::File;
+use super::tree::Node;
 
 pub struct Workspace {
     pub files: DashMap<String, File>,
+    trees: DashMap<PathBuf, Node>,
 }
 
 impl Debug for Workspace {
@@ -20,14 +23,42 @@ impl Workspace {
     pub fn new() -> Self {
         Workspace {
             files: DashMap::new(),
+            trees: DashMap::new(),
         }
     }
 
+    /// Returns the directory tree rooted at `dir`, reading it from disk and
+    /// caching the result the first time it is requested. Callers that
+    /// suspect the cache is stale (e.g. after a filesystem watcher event)
+    /// should use `invalidate_dir`.
+    pub fn dir_tree(&self, dir: &Path) -> Option<Node> {
+        if let Some(node) = self.trees.get(dir) {
+            return Some(node.clone());
+        }
+
+        let node = Node::read_dir(dir)?;
+        self.trees.insert(dir.to_path_buf(), node.clone());
+        Some(node)
+    }
+
+    pub fn invalidate_dir(&self, dir: &Path) {
+        self.trees.remove(dir);
+    }
+
     pub fn open(&self, uri: Url, text: String) {
         let file = File::new(uri, text);
         self.files.insert(file.url.to_string(), file);
     }
 
+    /// Like `open`, but a no-op if `uri` is already tracked. Lets a slower
+    /// background caller (e.g. workspace indexing's disk reads) register a
+    /// file without clobbering content a faster caller (e.g.
+    /// `textDocument/didOpen`) already opened, including any unsaved edits
+    /// the client made in between.
+    pub fn open_if_absent(&self, uri: Url, text: String) {
+        self.files.entry(uri.to_string()).or_insert_with(|| File::new(uri, text));
+    }
+
     pub fn close(&self, uri: &Url) {
         self.files.remove(&uri.to_string());
     }
@@ -79,4 +110,27 @@ mod tests {
 
         assert_eq!(workspace.files.len(), 1);
     }
+
+    #[test]
+    fn it_does_not_clobber_an_already_open_file() {
+        let workspace = Workspace::new();
+        let uri = Url::parse("file:///a").unwrap();
+
+        workspace.open(uri.clone(), "unsaved edit".to_string());
+        workspace.open_if_absent(uri.clone(), "stale disk content".to_string());
+
+        let file = workspace.files.get(&uri.to_string()).unwrap();
+        assert_eq!(file.get_content(), "unsaved edit");
+    }
+
+    #[test]
+    fn it_opens_an_absent_file() {
+        let workspace = Workspace::new();
+        let uri = Url::parse("file:///a").unwrap();
+
+        workspace.open_if_absent(uri.clone(), "content".to_string());
+
+        let file = workspace.files.get(&uri.to_string()).unwrap();
+        assert_eq!(file.get_content(), "content");
+    }
 }
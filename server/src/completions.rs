@@ -0,0 +1,289 @@
+use std::path::PathBuf;
+
+use dashmap::DashMap;
+use lsp_workspace::tree::Node;
+use lsp_workspace::workspace::Workspace;
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Documentation};
+use url::Url;
+
+use crate::parser::Token;
+
+/// Entries whose presence in a `.gitignore` candidate list is common enough
+/// to be worth a word of explanation, rather than just their bare name.
+const WELL_KNOWN: [(&str, &str); 5] = [
+    (
+        "node_modules",
+        "Node.js dependency directory. Regenerated from `package.json`, so it is almost always \
+         ignored.",
+    ),
+    ("target", "Cargo/Rust build output directory."),
+    (".DS_Store", "macOS Finder folder metadata file."),
+    ("dist", "Common build/bundle output directory for web projects."),
+    (
+        ".env",
+        "Local environment file. Usually holds secrets and should almost never be committed.",
+    ),
+];
+
+/// The payload carried in `CompletionItem::data`, letting `resolve` rebuild
+/// a lightweight item's detail/documentation without re-walking the parse
+/// tree or re-deriving the directory from the cursor position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CompletionData {
+    dir: PathBuf,
+    name: String,
+    is_dir: bool,
+}
+
+/// The detail/documentation `resolve` derives from a `CompletionData`
+/// payload, cached so that a client re-resolving the same still-highlighted
+/// item (Helix does this on every render frame) reuses the previous result
+/// instead of recomputing it.
+#[derive(Debug, Clone)]
+struct ResolvedCompletion {
+    detail: Option<String>,
+    documentation: Option<Documentation>,
+}
+
+/// Keyed by the `CompletionData` payload's canonical JSON encoding, since
+/// that's what uniquely identifies what a resolve would produce.
+pub type ResolvedCompletions = DashMap<String, ResolvedCompletion>;
+
+/// Offers path completions against the real filesystem, but keeps what
+/// `generate` returns cheap: each item carries only a label and a `data`
+/// payload, and the detail/documentation is filled in lazily by `resolve`
+/// when the client actually highlights it.
+pub struct Completions {}
+
+impl Completions {
+    /// Resolves the prefix under the cursor against the real directory that
+    /// contains `uri` and offers the child entries that match the last
+    /// partial segment, reading through `workspace`'s cached directory tree
+    /// so repeated completions in the same directory don't re-touch disk.
+    pub fn generate(
+        workspace: &Workspace,
+        uri: &Url,
+        root: &Token,
+        offset: usize,
+    ) -> Option<Vec<CompletionItem>> {
+        let lines = root.get_children()?;
+        let line = lines.iter().find(|line| line.contains_offset(offset))?;
+        let Token::Path { children, .. } = line else {
+            return None;
+        };
+
+        let mut typed_segments = vec![];
+        let mut prefix = String::new();
+        for child in children {
+            if child.contains_offset(offset) {
+                if let Token::Segment { value, .. } = child {
+                    prefix = value.clone();
+                }
+                break;
+            }
+            if let Token::Segment { value, .. } = child {
+                typed_segments.push(value.clone());
+            }
+        }
+
+        let gitignore_dir = uri.to_file_path().ok()?.parent()?.to_path_buf();
+        let dir = typed_segments
+            .iter()
+            .fold(gitignore_dir, |dir, segment| dir.join(segment));
+
+        let tree = workspace.dir_tree(&dir)?;
+        let entries = match &tree {
+            Node::Dir { children } => children,
+            Node::File { .. } => return None,
+        };
+
+        let items = entries
+            .iter()
+            .filter(|(name, _)| name.starts_with(prefix.as_str()))
+            .map(|(name, node)| {
+                let is_dir = node.is_dir();
+                let remaining = name[prefix.len()..].to_string();
+                let data = CompletionData {
+                    dir: dir.clone(),
+                    name: name.clone(),
+                    is_dir,
+                };
+
+                CompletionItem {
+                    label: name.clone(),
+                    kind: Some(if is_dir {
+                        CompletionItemKind::FOLDER
+                    } else {
+                        CompletionItemKind::FILE
+                    }),
+                    insert_text: Some(remaining),
+                    data: serde_json::to_value(&data).ok(),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Some(items)
+    }
+
+    /// Fills in the detail/documentation for a single item previously
+    /// emitted by `generate`, using the payload it carried in `data`.
+    /// `template_allow_list` restricts which `WELL_KNOWN` explanations are
+    /// used (empty means no restriction); a no-op if the item carries no
+    /// (or no longer recognisable) payload. `cache` is keyed on the item's
+    /// `CompletionData` payload, so repeat resolves of the same item reuse
+    /// the first result instead of re-deriving it.
+    pub fn resolve(
+        item: &mut CompletionItem,
+        template_allow_list: &[String],
+        cache: &ResolvedCompletions,
+    ) {
+        let Some(data) = item
+            .data
+            .clone()
+            .and_then(|value| serde_json::from_value::<CompletionData>(value).ok())
+        else {
+            return;
+        };
+
+        let Ok(key) = serde_json::to_string(&data) else {
+            return;
+        };
+
+        let resolved = cache
+            .entry(key)
+            .or_insert_with(|| ResolvedCompletion {
+                detail: Some(if data.is_dir {
+                    format!("{}/", data.name)
+                } else {
+                    data.name.clone()
+                }),
+                documentation: Some(Documentation::String(Self::describe(&data, template_allow_list))),
+            })
+            .clone();
+
+        item.detail = resolved.detail;
+        item.documentation = resolved.documentation;
+    }
+
+    fn describe(data: &CompletionData, template_allow_list: &[String]) -> String {
+        WELL_KNOWN
+            .iter()
+            .find(|(name, _)| {
+                *name == data.name
+                    && (template_allow_list.is_empty()
+                        || template_allow_list.iter().any(|allowed| allowed == name))
+            })
+            .map(|(_, doc)| doc.to_string())
+            .unwrap_or_else(|| {
+                let path = data.dir.join(&data.name).display().to_string();
+                if data.is_dir {
+                    format!("Directory at `{path}`.")
+                } else {
+                    format!("File at `{path}`.")
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser::parser;
+
+    fn setup(name: &str) -> (Url, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("gitignore-ultimate-completions-{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "").unwrap();
+
+        let uri = Url::from_file_path(dir.join(".gitignore")).unwrap();
+        (uri, dir)
+    }
+
+    fn generate(uri: &Url, text: &str, offset: usize) -> Vec<CompletionItem> {
+        let root = parser().parse(text).unwrap();
+        Completions::generate(&Workspace::new(), uri, &root, offset).unwrap_or_default()
+    }
+
+    #[test]
+    fn it_generates_lightweight_items_with_a_data_payload() {
+        let (uri, _dir) = setup("lightweight");
+        let items = generate(&uri, "Car", 3);
+
+        let cargo = items.iter().find(|item| item.label == "Cargo.toml").unwrap();
+        assert!(cargo.detail.is_none());
+        assert!(cargo.documentation.is_none());
+        assert!(cargo.data.is_some());
+    }
+
+    #[test]
+    fn it_descends_into_the_next_directory_level() {
+        let (uri, _dir) = setup("nested");
+        let items = generate(&uri, "src/", 4);
+        assert_eq!(items.len(), 0);
+    }
+
+    #[test]
+    fn it_resolves_detail_and_documentation_from_the_data_payload() {
+        let (uri, _dir) = setup("resolve");
+        let mut item = generate(&uri, "Car", 3)
+            .into_iter()
+            .find(|item| item.label == "Cargo.toml")
+            .unwrap();
+
+        Completions::resolve(&mut item, &[], &ResolvedCompletions::new());
+
+        assert_eq!(item.detail, Some("Cargo.toml".to_string()));
+        assert!(item.documentation.is_some());
+    }
+
+    #[test]
+    fn it_reuses_the_cached_result_on_a_repeat_resolve_of_the_same_item() {
+        let (uri, _dir) = setup("resolve-cached");
+        let item = generate(&uri, "Car", 3)
+            .into_iter()
+            .find(|item| item.label == "Cargo.toml")
+            .unwrap();
+        let cache = ResolvedCompletions::new();
+
+        let mut first = item.clone();
+        Completions::resolve(&mut first, &[], &cache);
+        assert_eq!(cache.len(), 1);
+
+        // A second resolve of the same item's data should hit the existing
+        // cache entry rather than inserting another one.
+        let mut second = item;
+        Completions::resolve(&mut second, &[], &cache);
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(second.detail, first.detail);
+        assert_eq!(second.documentation, first.documentation);
+    }
+
+    #[test]
+    fn it_describes_a_well_known_directory_without_needing_the_data_payload_recomputed() {
+        let data = CompletionData {
+            dir: PathBuf::from("/repo"),
+            name: "node_modules".to_string(),
+            is_dir: true,
+        };
+        assert!(Completions::describe(&data, &[]).contains("Regenerated"));
+    }
+
+    #[test]
+    fn it_omits_a_well_known_explanation_not_on_the_allow_list() {
+        let data = CompletionData {
+            dir: PathBuf::from("/repo"),
+            name: "node_modules".to_string(),
+            is_dir: true,
+        };
+        let allow_list = vec!["target".to_string()];
+        assert!(!Completions::describe(&data, &allow_list).contains("Regenerated"));
+    }
+}
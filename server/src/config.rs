@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::DiagnosticSeverity;
+
+use crate::format::FormatOptions;
+
+/// Toggles for the semantic (redundant/shadowed-rule) pass in
+/// `Diagnostics::generate`, kept separate from the syntactic lints so a
+/// user who finds the heuristics noisy can turn them off, or just dial
+/// down their severity, without losing parse errors and the cheap
+/// per-line hints.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SemanticDiagnosticsConfig {
+    pub enabled: bool,
+    pub severity: DiagnosticSeverity,
+}
+
+impl Default for SemanticDiagnosticsConfig {
+    fn default() -> Self {
+        SemanticDiagnosticsConfig {
+            enabled: true,
+            severity: DiagnosticSeverity::WARNING,
+        }
+    }
+}
+
+/// Restricts which of `completions::WELL_KNOWN` entries get their
+/// explanation filled in by `resolve`. Empty (the default) means no
+/// restriction, since most users have no opinion on this and an empty
+/// allow-list that hid every explanation by default would be a surprising
+/// regression.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompletionsConfig {
+    pub template_allow_list: Vec<String>,
+}
+
+/// Caps how many workspace files a single "go to" on a pattern can return,
+/// so a broad rule like `*` in a large workspace can't flood the client.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReferencesConfig {
+    pub max_matches: usize,
+}
+
+impl Default for ReferencesConfig {
+    fn default() -> Self {
+        ReferencesConfig { max_matches: 100 }
+    }
+}
+
+/// Server-wide settings, read from `InitializeParams.initialization_options`
+/// at startup and replaced wholesale on every `workspace/didChangeConfiguration`
+/// notification, mirroring texlab's `ConfigManager`. Unknown fields are
+/// ignored and missing sections fall back to their defaults rather than
+/// failing, so a client that only sends `{"format": {...}}` still gets its
+/// format settings applied, with every other section at its default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub diagnostics: SemanticDiagnosticsConfig,
+    pub format: FormatOptions,
+    pub completions: CompletionsConfig,
+    pub references: ReferencesConfig,
+}
+
+impl Config {
+    /// Parses `initialization_options`/`didChangeConfiguration` settings,
+    /// falling back to defaults for a missing or malformed payload rather
+    /// than failing initialization over it.
+    pub fn parse(value: Option<serde_json::Value>) -> Config {
+        value
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
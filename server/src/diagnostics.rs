@@ -0,0 +1,582 @@
+use chumsky::span::SimpleSpan;
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Range, Url,
+};
+
+use crate::config::SemanticDiagnosticsConfig;
+use crate::ignore_rule::IgnoreRule;
+use crate::incremental::{LineError, ReparseResult};
+use crate::parser::Token;
+use crate::source_map::LineIndex;
+use crate::suggestion::{Applicability, Suggestion};
+
+pub struct Diagnostics {}
+
+impl Diagnostics {
+    /// Builds diagnostics for a reparse: one per parse error (with a fix-it
+    /// when the error looks like a doubled `//`), lints for segments and
+    /// comments the grammar accepts but that are probably mistakes, plus a
+    /// semantic pass over the compiled rules for ones that have no effect,
+    /// which `semantic` can disable or re-tier.
+    pub fn generate(
+        result: &ReparseResult,
+        text: &str,
+        line_index: &LineIndex,
+        uri: &Url,
+        semantic: SemanticDiagnosticsConfig,
+    ) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<Diagnostic> = result
+            .errors
+            .iter()
+            .map(|error| Self::from_parse_error(error, text, line_index))
+            .collect();
+
+        if let Some(children) = result.token.get_children() {
+            Self::lint_lines(children, text, line_index, &mut diagnostics);
+            if semantic.enabled {
+                Self::lint_semantic(children, text, line_index, uri, semantic.severity, &mut diagnostics);
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Simulates gitignore precedence in file order: for each rule, checks
+    /// whether it has no effect, either because it's a plain duplicate of an
+    /// earlier rule, because an earlier broader rule of the same polarity
+    /// already covers everything it matches, because a later negation always
+    /// undoes it, or — for a negation — because no earlier positive rule
+    /// could have matched its target in the first place, or an ancestor
+    /// directory of its target is excluded and never re-included.
+    fn lint_semantic(
+        lines: &[Token],
+        text: &str,
+        line_index: &LineIndex,
+        uri: &Url,
+        severity: DiagnosticSeverity,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let compiled: Vec<(&Token, IgnoreRule)> = lines
+            .iter()
+            .filter_map(|line| IgnoreRule::compile(line).map(|rule| (line, rule)))
+            .collect();
+        let rules: Vec<IgnoreRule> = compiled.iter().map(|(_, rule)| rule.clone()).collect();
+
+        let mut seen: Vec<(&Token, IgnoreRule)> = vec![];
+
+        for (index, (line, rule)) in compiled.iter().enumerate() {
+            if let Some((earlier, _)) = seen.iter().find(|(_, earlier_rule)| earlier_rule == rule) {
+                diagnostics.push(Self::shadowed_diagnostic(
+                    text,
+                    line_index,
+                    uri,
+                    severity,
+                    line.get_span(),
+                    earlier.get_span(),
+                    "This pattern duplicates an earlier rule and has no additional effect."
+                        .to_string(),
+                    "The earlier rule this pattern is shadowed by.",
+                ));
+            } else if let Some((earlier, _)) = seen.iter().rev().find(|(_, earlier_rule)| {
+                earlier_rule.negate == rule.negate && earlier_rule.subsumes(rule)
+            }) {
+                diagnostics.push(Self::shadowed_diagnostic(
+                    text,
+                    line_index,
+                    uri,
+                    severity,
+                    line.get_span(),
+                    earlier.get_span(),
+                    "This pattern is already covered by an earlier, broader rule and has no \
+                     additional effect."
+                        .to_string(),
+                    "The earlier rule this pattern is shadowed by.",
+                ));
+            } else if let Some((later, _)) = (!rule.negate)
+                .then(|| &compiled[index + 1..])
+                .and_then(|later_lines| {
+                    later_lines
+                        .iter()
+                        .find(|(_, later_rule)| later_rule.negate && later_rule.subsumes(rule))
+                })
+            {
+                diagnostics.push(Self::shadowed_diagnostic(
+                    text,
+                    line_index,
+                    uri,
+                    severity,
+                    line.get_span(),
+                    later.get_span(),
+                    "This pattern's effect is always undone by a later negation and has no \
+                     effect of its own."
+                        .to_string(),
+                    "The later negation that always undoes this pattern.",
+                ));
+            } else if rule.negate
+                && !seen
+                    .iter()
+                    .any(|(_, earlier_rule)| !earlier_rule.negate && earlier_rule.overlaps(rule))
+            {
+                diagnostics.push(Diagnostic::new(
+                    Self::range(text, line_index, line.get_span().start, line.get_span().end),
+                    Some(severity),
+                    None,
+                    Some("Gitignore Ultimate".to_string()),
+                    "This negation can never take effect: no earlier pattern would have \
+                     ignored the paths it targets."
+                        .to_string(),
+                    None,
+                    None,
+                ));
+            } else if rule.negate && rule.blocked_by_ancestor_exclusion(&rules) {
+                diagnostics.push(Diagnostic::new(
+                    Self::range(text, line_index, line.get_span().start, line.get_span().end),
+                    Some(severity),
+                    None,
+                    Some("Gitignore Ultimate".to_string()),
+                    "This negation can never take effect: one of its ancestor directories is \
+                     excluded and never re-included, so git never looks inside it."
+                        .to_string(),
+                    None,
+                    None,
+                ));
+            }
+
+            seen.push((line, rule.clone()));
+        }
+    }
+
+    /// A diagnostic pointing at `span`, with `related_span` (the other rule
+    /// that makes it redundant) attached as `related_information` under
+    /// `related_message`.
+    fn shadowed_diagnostic(
+        text: &str,
+        line_index: &LineIndex,
+        uri: &Url,
+        severity: DiagnosticSeverity,
+        span: &SimpleSpan,
+        related_span: &SimpleSpan,
+        message: String,
+        related_message: &str,
+    ) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            Self::range(text, line_index, span.start, span.end),
+            Some(severity),
+            None,
+            Some("Gitignore Ultimate".to_string()),
+            message,
+            None,
+            None,
+        );
+        diagnostic.related_information = Some(vec![DiagnosticRelatedInformation {
+            location: Location {
+                uri: uri.clone(),
+                range: Self::range(text, line_index, related_span.start, related_span.end),
+            },
+            message: related_message.to_string(),
+        }]);
+        diagnostic
+    }
+
+    fn from_parse_error(error: &LineError, text: &str, line_index: &LineIndex) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            Self::range(text, line_index, error.span.start, error.span.end),
+            Some(DiagnosticSeverity::ERROR),
+            None,
+            Some("Gitignore Ultimate".to_string()),
+            error.message.clone(),
+            None,
+            None,
+        );
+
+        if let Some(suggestion) = Self::suggest_double_separator_fix(text, error.span.start, line_index) {
+            diagnostic.data = serde_json::to_value(suggestion).ok();
+        }
+
+        diagnostic
+    }
+
+    /// A parse failure at a `/` that directly follows another `/` is, in
+    /// practice, almost always a doubled separator — offer to collapse it.
+    fn suggest_double_separator_fix(
+        text: &str,
+        offset: usize,
+        line_index: &LineIndex,
+    ) -> Option<Suggestion> {
+        let bytes = text.as_bytes();
+        let start = offset.checked_sub(1).filter(|&i| bytes.get(i) == Some(&b'/'))?;
+        if bytes.get(offset) != Some(&b'/') {
+            return None;
+        }
+
+        Some(Suggestion {
+            range: Self::range(text, line_index, start, offset + 1),
+            replacement: "/".to_string(),
+            applicability: Applicability::MaybeIncorrect,
+        })
+    }
+
+    fn lint_lines(
+        lines: &[Token],
+        text: &str,
+        line_index: &LineIndex,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        for line in lines {
+            match line {
+                Token::Comment { span } => {
+                    diagnostics.push(Self::comment_escape_hint(text, line_index, span));
+                }
+                Token::Path { children, .. } => {
+                    for child in children {
+                        match child {
+                            Token::Segment { span, value } => {
+                                if let Some(diagnostic) =
+                                    Self::trailing_whitespace_hint(text, line_index, span, value)
+                                {
+                                    diagnostics.push(diagnostic);
+                                }
+                            }
+                            Token::CharClass { span, negated, value } => {
+                                if let Some(diagnostic) =
+                                    Self::char_class_hint(text, line_index, span, *negated, value)
+                                {
+                                    diagnostics.push(diagnostic);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    for component in Self::path_components(children) {
+                        diagnostics.extend(Self::embedded_double_star_hints(text, line_index, component));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Splits a `Path`'s flat child list into its path components, i.e. the
+    /// runs of tokens between `Separator`s (a leading `Negate` belongs to no
+    /// component and is dropped).
+    fn path_components(children: &[Token]) -> Vec<&[Token]> {
+        let mut components = vec![];
+        let mut start = 0;
+        for (i, child) in children.iter().enumerate() {
+            match child {
+                Token::Separator { .. } | Token::Negate { .. } => {
+                    if i > start {
+                        components.push(&children[start..i]);
+                    }
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        if children.len() > start {
+            components.push(&children[start..]);
+        }
+        components
+    }
+
+    /// `**` only spans directory boundaries when it is a whole path
+    /// component; two adjacent stars next to other characters (e.g. `a**b`)
+    /// just match `*` twice, which is almost never what was intended. A
+    /// component can contain more than one such occurrence (`a**b**c`), so
+    /// every non-overlapping adjacent pair is flagged.
+    fn embedded_double_star_hints(
+        text: &str,
+        line_index: &LineIndex,
+        component: &[Token],
+    ) -> Vec<Diagnostic> {
+        if component.len() <= 2 {
+            return vec![];
+        }
+
+        let mut diagnostics = vec![];
+        let mut i = 0;
+        while i + 1 < component.len() {
+            let (Token::Star { span: a }, Token::Star { span: b }) = (&component[i], &component[i + 1])
+            else {
+                i += 1;
+                continue;
+            };
+            if a.end != b.start {
+                i += 1;
+                continue;
+            }
+
+            diagnostics.push(Diagnostic::new(
+                Self::range(text, line_index, a.start, b.end),
+                Some(DiagnosticSeverity::WARNING),
+                None,
+                Some("Gitignore Ultimate".to_string()),
+                "`**` only spans directories when it is a whole path component; here it is mixed \
+                 with other characters, so it just matches `*` twice."
+                    .to_string(),
+                None,
+                None,
+            ));
+            i += 2;
+        }
+
+        diagnostics
+    }
+
+    fn char_class_hint(
+        text: &str,
+        line_index: &LineIndex,
+        span: &SimpleSpan,
+        negated: bool,
+        value: &str,
+    ) -> Option<Diagnostic> {
+        let terminated = text.as_bytes().get(span.end.wrapping_sub(1)) == Some(&b']');
+
+        let message = if !terminated {
+            "This character class is missing its closing `]`."
+        } else if value.is_empty() && negated {
+            "This negated character class has nothing to exclude, so it matches any single \
+             character, like `?`."
+        } else if value.is_empty() {
+            "This character class is empty and matches nothing."
+        } else {
+            return None;
+        };
+
+        Some(Diagnostic::new(
+            Self::range(text, line_index, span.start, span.end),
+            Some(DiagnosticSeverity::WARNING),
+            None,
+            Some("Gitignore Ultimate".to_string()),
+            message.to_string(),
+            None,
+            None,
+        ))
+    }
+
+    fn comment_escape_hint(text: &str, line_index: &LineIndex, span: &SimpleSpan) -> Diagnostic {
+        let suggestion = Suggestion {
+            range: Self::range(text, line_index, span.start, span.start + 1),
+            replacement: "\\#".to_string(),
+            applicability: Applicability::MaybeIncorrect,
+        };
+
+        let mut diagnostic = Diagnostic::new(
+            Self::range(text, line_index, span.start, span.end),
+            Some(DiagnosticSeverity::HINT),
+            None,
+            Some("Gitignore Ultimate".to_string()),
+            "This line is a comment and is ignored. To match a file that begins with '#', \
+             escape it as '\\#'."
+                .to_string(),
+            None,
+            None,
+        );
+        diagnostic.data = serde_json::to_value(suggestion).ok();
+        diagnostic
+    }
+
+    fn trailing_whitespace_hint(
+        text: &str,
+        line_index: &LineIndex,
+        span: &SimpleSpan,
+        value: &str,
+    ) -> Option<Diagnostic> {
+        let raw = &text[span.start..span.end];
+        if raw == value {
+            return None;
+        }
+
+        let range = Self::range(text, line_index, span.start, span.end);
+        let suggestion = Suggestion {
+            range,
+            replacement: value.to_string(),
+            applicability: Applicability::MachineApplicable,
+        };
+
+        let mut diagnostic = Diagnostic::new(
+            range,
+            Some(DiagnosticSeverity::WARNING),
+            None,
+            Some("Gitignore Ultimate".to_string()),
+            "Whitespace around this segment is trimmed and has no effect on matching.".to_string(),
+            None,
+            None,
+        );
+        diagnostic.data = serde_json::to_value(suggestion).ok();
+        Some(diagnostic)
+    }
+
+    fn range(text: &str, line_index: &LineIndex, start: usize, end: usize) -> Range {
+        Range::new(line_index.position(text, start), line_index.position(text, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::incremental::IncrementalParser;
+
+    fn generate(text: &str) -> Vec<Diagnostic> {
+        let mut cache = vec![];
+        let result = IncrementalParser::reparse(text, &mut cache);
+        let line_index = LineIndex::new(text);
+        let uri = Url::parse("file:///.gitignore").unwrap();
+        Diagnostics::generate(&result, text, &line_index, &uri, SemanticDiagnosticsConfig::default())
+    }
+
+    #[test]
+    fn it_suppresses_semantic_diagnostics_when_disabled() {
+        let mut cache = vec![];
+        let text = "*.log\ndebug.log\n";
+        let result = IncrementalParser::reparse(text, &mut cache);
+        let line_index = LineIndex::new(text);
+        let uri = Url::parse("file:///.gitignore").unwrap();
+        let semantic = SemanticDiagnosticsConfig {
+            enabled: false,
+            ..SemanticDiagnosticsConfig::default()
+        };
+        let diagnostics = Diagnostics::generate(&result, text, &line_index, &uri, semantic);
+        assert_eq!(diagnostics.len(), 0);
+    }
+
+    #[test]
+    fn it_honors_a_configured_semantic_severity() {
+        let mut cache = vec![];
+        let text = "*.log\ndebug.log\n";
+        let result = IncrementalParser::reparse(text, &mut cache);
+        let line_index = LineIndex::new(text);
+        let uri = Url::parse("file:///.gitignore").unwrap();
+        let semantic = SemanticDiagnosticsConfig {
+            enabled: true,
+            severity: DiagnosticSeverity::HINT,
+        };
+        let diagnostics = Diagnostics::generate(&result, text, &line_index, &uri, semantic);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::HINT));
+    }
+
+    #[test]
+    fn it_suggests_collapsing_a_doubled_separator() {
+        let diagnostics = generate("a//b");
+        let suggestion: Suggestion =
+            serde_json::from_value(diagnostics[0].data.clone().unwrap()).unwrap();
+        assert_eq!(suggestion.replacement, "/");
+        assert_eq!(suggestion.applicability, Applicability::MaybeIncorrect);
+    }
+
+    #[test]
+    fn it_suggests_trimming_trailing_whitespace_in_a_segment() {
+        let diagnostics = generate("a/b  \n");
+        assert_eq!(diagnostics.len(), 1);
+        let suggestion: Suggestion =
+            serde_json::from_value(diagnostics[0].data.clone().unwrap()).unwrap();
+        assert_eq!(suggestion.replacement, "b");
+        assert_eq!(suggestion.applicability, Applicability::MachineApplicable);
+    }
+
+    #[test]
+    fn it_hints_at_escaping_an_unescaped_leading_hash() {
+        let diagnostics = generate("# not a comment?\n");
+        assert_eq!(diagnostics.len(), 1);
+        let suggestion: Suggestion =
+            serde_json::from_value(diagnostics[0].data.clone().unwrap()).unwrap();
+        assert_eq!(suggestion.replacement, "\\#");
+    }
+
+    #[test]
+    fn it_parses_a_tidy_file_without_any_diagnostics() {
+        assert_eq!(generate("a/b/c\n").len(), 0);
+    }
+
+    #[test]
+    fn it_warns_about_a_double_star_mixed_with_other_characters() {
+        let diagnostics = generate("a**b\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("whole path component"));
+    }
+
+    #[test]
+    fn it_does_not_warn_about_a_double_star_that_is_a_whole_component() {
+        assert_eq!(generate("**/build\n").len(), 0);
+    }
+
+    #[test]
+    fn it_warns_about_an_unterminated_character_class() {
+        let diagnostics = generate("[a-z\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("closing `]`"));
+    }
+
+    #[test]
+    fn it_warns_about_an_empty_character_class() {
+        let diagnostics = generate("[]\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("empty"));
+    }
+
+    #[test]
+    fn it_warns_differently_about_a_negated_empty_character_class() {
+        let diagnostics = generate("[!]\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("matches any single character"));
+    }
+
+    #[test]
+    fn it_warns_about_every_embedded_double_star_in_a_component() {
+        let diagnostics = generate("a**b**c\n");
+        assert_eq!(diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn it_warns_about_a_duplicate_pattern() {
+        let diagnostics = generate("*.log\n*.log\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("duplicates an earlier rule"));
+        assert!(diagnostics[0].related_information.is_some());
+    }
+
+    #[test]
+    fn it_warns_about_a_pattern_subsumed_by_an_earlier_broader_one() {
+        let diagnostics = generate("*.log\ndebug.log\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("already covered"));
+    }
+
+    #[test]
+    fn it_does_not_warn_when_the_narrower_pattern_comes_first() {
+        assert_eq!(generate("debug.log\n*.log\n").len(), 0);
+    }
+
+    #[test]
+    fn it_warns_about_a_negation_with_no_earlier_rule_to_undo() {
+        let diagnostics = generate("!debug.log\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("can never take effect"));
+    }
+
+    #[test]
+    fn it_does_not_warn_about_a_negation_an_earlier_rule_could_have_matched() {
+        assert_eq!(generate("*.log\n!debug.log\n").len(), 0);
+    }
+
+    #[test]
+    fn it_warns_about_a_rule_made_dead_by_a_later_negation() {
+        let diagnostics = generate("debug.log\n!debug.log\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("undone by a later negation"));
+        assert!(diagnostics[0].related_information.is_some());
+    }
+
+    #[test]
+    fn it_does_not_warn_about_a_negation_that_only_partially_overlaps_an_earlier_rule() {
+        assert_eq!(generate("*.log\n!important.*\n").len(), 0);
+    }
+
+    #[test]
+    fn it_warns_about_a_negation_blocked_by_an_ancestor_exclusion() {
+        let diagnostics = generate("**\n!build/sub/keep\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("ancestor"));
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::parser::{trim_trailing_unescaped_whitespace, Token};
+
+/// Toggles for the parts of formatting that change the meaning of a file
+/// rather than just its whitespace, so callers (e.g. a user's
+/// `didChangeConfiguration`) can opt out of reordering or de-duplicating
+/// without losing the always-safe whitespace cleanup.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatOptions {
+    /// Sort pattern entries alphabetically within each comment-delimited
+    /// section. Off by default: reordering can silently change what a
+    /// negation re-includes when rules overlap.
+    pub sort_entries: bool,
+    /// Drop a pattern line that textually repeats an earlier one in the
+    /// same section. Always safe, since an exact duplicate has no
+    /// additional effect wherever it appears.
+    pub deduplicate: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            sort_entries: false,
+            deduplicate: true,
+        }
+    }
+}
+
+/// Renders a canonical form of a parsed `.gitignore` file straight from the
+/// token tree's spans, so the output always matches what the parser actually
+/// consumed: trailing whitespace is trimmed from each line (unless escaped
+/// with a backslash), runs of blank lines collapse to a single blank line,
+/// and each comment-delimited run of pattern entries is optionally
+/// de-duplicated and sorted per `FormatOptions`.
+pub struct Formatter {}
+
+impl Formatter {
+    pub fn format(source: &str, root: &Token, options: FormatOptions) -> String {
+        let children = match root.get_children() {
+            Some(children) => children,
+            None => return source.to_string(),
+        };
+
+        let mut out = String::new();
+        let mut previous_blank = false;
+        let mut section: Vec<&str> = vec![];
+
+        for (index, child) in children.iter().enumerate() {
+            let span = child.get_span();
+            let line = trim_trailing_unescaped_whitespace(&source[span.start..span.end]);
+            let blank = line.is_empty();
+            let is_comment = matches!(child, Token::Comment { .. });
+
+            if blank || is_comment {
+                Self::flush_section(&mut out, &mut section, options);
+
+                // The empty last child after the file's final newline ends
+                // the previous line; it is not a blank line of its own.
+                let at_end = index + 1 == children.len();
+                if blank && (previous_blank || at_end) {
+                    continue;
+                }
+
+                out.push_str(line);
+                out.push('\n');
+                previous_blank = blank;
+                continue;
+            }
+
+            section.push(line);
+            previous_blank = false;
+        }
+
+        Self::flush_section(&mut out, &mut section, options);
+
+        out
+    }
+
+    /// Applies de-duplication and/or sorting to one comment-delimited run of
+    /// pattern lines and appends it to `out`. Comments and blank lines
+    /// bound a section but are never themselves reordered, so a
+    /// `# Build artifacts` header always stays directly above its group.
+    fn flush_section(out: &mut String, section: &mut Vec<&str>, options: FormatOptions) {
+        let mut lines: Vec<&str> = std::mem::take(section);
+
+        if options.deduplicate {
+            let mut seen = HashSet::new();
+            lines.retain(|line| seen.insert(*line));
+        }
+        if options.sort_entries {
+            lines.sort_unstable();
+        }
+
+        for line in lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser::parser;
+
+    fn format(source: &str) -> String {
+        format_with(source, FormatOptions::default())
+    }
+
+    fn format_with(source: &str, options: FormatOptions) -> String {
+        let root = parser().parse(source).unwrap();
+        Formatter::format(source, &root, options)
+    }
+
+    #[test]
+    fn it_trims_trailing_whitespace() {
+        assert_eq!(format("a/b/c   \n"), "a/b/c\n");
+    }
+
+    #[test]
+    fn it_keeps_an_escaped_trailing_space() {
+        assert_eq!(format("a/b/c\\ \n"), "a/b/c\\ \n");
+    }
+
+    #[test]
+    fn it_trims_trailing_whitespace_after_an_escaped_backslash() {
+        assert_eq!(format("a/b/c\\\\  \n"), "a/b/c\\\\\n");
+    }
+
+    #[test]
+    fn it_collapses_consecutive_blank_lines() {
+        assert_eq!(format("a\n\n\n\nb\n"), "a\n\nb\n");
+    }
+
+    #[test]
+    fn it_deduplicates_an_identical_line_by_default() {
+        assert_eq!(format("*.log\n*.log\n"), "*.log\n");
+    }
+
+    #[test]
+    fn it_does_not_sort_entries_by_default() {
+        assert_eq!(format("b\na\n"), "b\na\n");
+    }
+
+    #[test]
+    fn it_can_sort_entries_within_a_section_when_enabled() {
+        let options = FormatOptions {
+            sort_entries: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(format_with("b\na\nc\n", options), "a\nb\nc\n");
+    }
+
+    #[test]
+    fn it_keeps_a_comment_header_directly_above_its_sorted_group() {
+        let options = FormatOptions {
+            sort_entries: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format_with("# Build artifacts\nb\na\n", options),
+            "# Build artifacts\na\nb\n"
+        );
+    }
+
+    #[test]
+    fn it_does_not_sort_across_a_section_boundary() {
+        let options = FormatOptions {
+            sort_entries: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format_with("b\n# Section\na\n", options),
+            "b\n# Section\na\n"
+        );
+    }
+
+    #[test]
+    fn it_can_disable_deduplication() {
+        let options = FormatOptions {
+            deduplicate: false,
+            ..FormatOptions::default()
+        };
+        assert_eq!(format_with("*.log\n*.log\n", options), "*.log\n*.log\n");
+    }
+}
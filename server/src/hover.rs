@@ -0,0 +1,175 @@
+use tower_lsp::lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind};
+
+use crate::parser::Token;
+
+/// Turns the token tree into the explanation a reviewer would give for a
+/// rule: is it anchored, directory-only, negated, and what do its wildcards
+/// actually match. Mirrors texlab's `HoverProvider` in shape: a single
+/// static entry point that resolves the node under an offset and renders
+/// Markdown for it.
+pub struct HoverProvider {}
+
+impl HoverProvider {
+    /// Resolves the line under `offset` and renders Markdown describing it,
+    /// or `None` if the offset falls on a blank line or past the end of the
+    /// file.
+    pub fn hover(text: &str, root: &Token, offset: usize) -> Option<Hover> {
+        let lines = root.get_children()?;
+        let line = lines.iter().find(|line| line.contains_offset(offset))?;
+
+        let value = match line {
+            Token::Comment { .. } => {
+                "This line is a comment and is ignored when matching files.".to_string()
+            }
+            Token::Path { .. } => Self::describe_path(text, line)?,
+            _ => return None,
+        };
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    }
+
+    /// Renders a `Path`'s rule as Markdown: the raw pattern, then one
+    /// bullet per semantic fact (negation, anchoring, directory-only,
+    /// wildcard meanings) that is actually true of this rule.
+    fn describe_path(text: &str, line: &Token) -> Option<String> {
+        let (negate, dir_only, content) = line.decompose_path()?;
+
+        // A leading `**/` matches at any depth, so it doesn't anchor the
+        // pattern even though it contains a `/`.
+        let anchored = !matches!(content.first(), Some(Token::DoubleStar { .. }))
+            && content.iter().any(|token| matches!(token, Token::Separator { .. }));
+        let start = content.first()?.get_span().start;
+        let end = content.last()?.get_span().end;
+        let pattern = &text[start..end];
+
+        let mut bullets = vec![];
+
+        if negate {
+            bullets.push(
+                "**Negated** (`!`): re-includes paths that an earlier rule ignored, instead of \
+                 ignoring them."
+                    .to_string(),
+            );
+        }
+        if anchored {
+            bullets.push(
+                "**Anchored**: the `/` ties this pattern to this .gitignore's own directory, so \
+                 it only matches there, not in subdirectories."
+                    .to_string(),
+            );
+        } else {
+            bullets.push(
+                "**Unanchored**: matches a path component of this name at any depth under this \
+                 .gitignore's directory."
+                    .to_string(),
+            );
+        }
+        if dir_only {
+            bullets.push(
+                "**Directory-only** (trailing `/`): only matches directories, never plain files."
+                    .to_string(),
+            );
+        }
+
+        for wildcard in Self::wildcard_explanations(content) {
+            bullets.push(wildcard);
+        }
+
+        let mut markdown = format!("**Pattern**: `{pattern}`\n\n");
+        for bullet in bullets {
+            markdown.push_str("- ");
+            markdown.push_str(&bullet);
+            markdown.push('\n');
+        }
+
+        Some(markdown.trim_end().to_string())
+    }
+
+    /// One bullet per distinct wildcard kind present in the pattern,
+    /// in the order each first appears.
+    fn wildcard_explanations(content: &[Token]) -> Vec<String> {
+        let mut explanations = vec![];
+        for token in content {
+            let explanation = match token {
+                Token::DoubleStar { .. } => {
+                    "`**` matches zero or more whole directories, so it spans any number of \
+                     path segments."
+                }
+                Token::Star { .. } => "`*` matches any run of characters other than `/`.",
+                Token::AnyChar { .. } => "`?` matches any single character other than `/`.",
+                Token::CharClass { negated: false, .. } => {
+                    "`[...]` matches any one of the listed characters."
+                }
+                Token::CharClass { negated: true, .. } => {
+                    "`[!...]` matches any character other than the ones listed."
+                }
+                _ => continue,
+            };
+            if !explanations.contains(&explanation) {
+                explanations.push(explanation);
+            }
+        }
+        explanations.into_iter().map(str::to_string).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser::parser;
+
+    fn hover(text: &str, offset: usize) -> Option<Hover> {
+        let root = parser().parse(text).unwrap();
+        HoverProvider::hover(text, &root, offset)
+    }
+
+    fn markdown(hover: Hover) -> String {
+        match hover.contents {
+            HoverContents::Markup(content) => content.value,
+            _ => panic!("expected markup contents"),
+        }
+    }
+
+    #[test]
+    fn it_explains_a_comment() {
+        let hover = hover("# hello\n", 2).unwrap();
+        assert!(markdown(hover).contains("comment"));
+    }
+
+    #[test]
+    fn it_explains_an_anchored_directory_only_pattern() {
+        let hover = hover("/build/\n", 2).unwrap();
+        let markdown = markdown(hover);
+        assert!(markdown.contains("Anchored"));
+        assert!(markdown.contains("Directory-only"));
+    }
+
+    #[test]
+    fn it_explains_an_unanchored_wildcard_pattern() {
+        let hover = hover("*.log\n", 0).unwrap();
+        let markdown = markdown(hover);
+        assert!(markdown.contains("Unanchored"));
+        assert!(markdown.contains("`*` matches any run of characters"));
+    }
+
+    #[test]
+    fn it_explains_a_negated_double_star_pattern() {
+        let hover = hover("!**/build\n", 0).unwrap();
+        let markdown = markdown(hover);
+        assert!(markdown.contains("Negated"));
+        assert!(markdown.contains("spans"));
+    }
+
+    #[test]
+    fn it_returns_none_for_a_blank_line() {
+        assert!(hover("a/b\n\nc/d\n", 4).is_none());
+    }
+}
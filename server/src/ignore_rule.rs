@@ -0,0 +1,426 @@
+use crate::parser::Token;
+
+/// A single `.gitignore` rule translated into a form that can be matched
+/// against path components, independent of the token tree it came from.
+/// It only answers "does this rule match this path" and how it compares
+/// with another rule (`subsumes`, `overlaps`); walking a directory tree to
+/// find which files a set of rules ignores is left to `Matcher`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoreRule {
+    pub negate: bool,
+    anchored: bool,
+    dir_only: bool,
+    components: Vec<String>,
+}
+
+impl IgnoreRule {
+    /// Builds an `IgnoreRule` from a `Token::Path`, or `None` if the path
+    /// has no matchable content (e.g. a blank line).
+    pub fn compile(path: &Token) -> Option<IgnoreRule> {
+        let (negate, dir_only, content) = path.decompose_path()?;
+
+        let separator_count = content
+            .iter()
+            .filter(|token| matches!(token, Token::Separator { .. }))
+            .count();
+        let anchored = separator_count > 0;
+
+        let mut components = vec![];
+        let mut current = String::new();
+        for token in content {
+            match token {
+                Token::Separator { .. } => components.push(std::mem::take(&mut current)),
+                Token::Segment { value, .. } => current.push_str(value),
+                Token::Star { .. } => current.push('*'),
+                Token::DoubleStar { .. } => current.push_str("**"),
+                Token::AnyChar { .. } => current.push('?'),
+                Token::CharClass { negated, value, .. } => {
+                    current.push('[');
+                    if *negated {
+                        current.push('!');
+                    }
+                    current.push_str(value);
+                    current.push(']');
+                }
+                _ => {}
+            }
+        }
+        components.push(current);
+
+        // A leading `/` anchors the pattern but doesn't introduce a real
+        // (empty) component to match against.
+        if anchored && components.first().map(String::is_empty).unwrap_or(false) {
+            components.remove(0);
+        }
+
+        Some(IgnoreRule {
+            negate,
+            anchored,
+            dir_only,
+            components,
+        })
+    }
+
+    /// Whether this rule's pattern matches `path_components`, a relative
+    /// path split on `/` and rooted at the `.gitignore`'s directory.
+    /// `is_dir` must say whether the candidate itself is a directory: a
+    /// directory-only rule (trailing `/`) never matches a plain file, no
+    /// matter how well its pattern would otherwise line up.
+    pub fn matches(&self, path_components: &[String], is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.anchored {
+            match_components(&self.components, path_components)
+        } else {
+            (0..=path_components.len())
+                .any(|start| match_components(&self.components, &path_components[start..]))
+        }
+    }
+
+    /// Whether every path `other` could match is also matched by `self`,
+    /// so `other` has no effect once `self` is already in the file (when
+    /// both have the same polarity). This is a conservative syntactic check
+    /// over the segment/`**` structure, not full glob-containment: a
+    /// wildcard component of `self` is only recognised as covering `other`'s
+    /// corresponding component when that component has no wildcards of its
+    /// own, see `component_contains`.
+    pub fn subsumes(&self, other: &IgnoreRule) -> bool {
+        if self.anchored != other.anchored {
+            return false;
+        }
+        // A directory-only rule only ever ignores directories, so it can't
+        // subsume a rule that also matches plain files.
+        if self.dir_only && !other.dir_only {
+            return false;
+        }
+        subsumes_components(&self.components, &other.components)
+    }
+
+    /// Whether some path could match both `self` and `other`, unlike
+    /// `subsumes` which asks whether `self` matches *every* path `other`
+    /// does. A directory-only rule never disqualifies overlap: any shared
+    /// path can always be taken as a directory, which satisfies `dir_only`
+    /// on either side regardless of the other rule's own flag.
+    pub fn overlaps(&self, other: &IgnoreRule) -> bool {
+        overlaps_components(&self.effective_components(), &other.effective_components())
+    }
+
+    /// This rule's components, with an unanchored pattern's implicit "match
+    /// starting at any depth" made explicit as a leading `**`, the way
+    /// `matches` achieves it by trying every start offset instead.
+    fn effective_components(&self) -> Vec<String> {
+        if self.anchored {
+            self.components.clone()
+        } else {
+            let mut components = vec!["**".to_string()];
+            components.extend(self.components.iter().cloned());
+            components
+        }
+    }
+
+    /// Whether this rule can never take effect because one of its ancestor
+    /// directories is excluded by `rules` (every compiled rule in the same
+    /// file, in original order) and never re-included: git doesn't descend
+    /// into an excluded directory, so nothing below it is ever checked
+    /// against a later rule, however precisely that rule matches. Only
+    /// meaningful for a negation — a plain ignore rule blocked the same way
+    /// simply has no observable effect, rather than a dead one.
+    pub fn blocked_by_ancestor_exclusion(&self, rules: &[IgnoreRule]) -> bool {
+        (1..self.components.len()).any(|depth| {
+            let ancestor = &self.components[..depth];
+            rules.iter().fold(false, |ignored, rule| {
+                if rule.matches(ancestor, true) {
+                    !rule.negate
+                } else {
+                    ignored
+                }
+            })
+        })
+    }
+}
+
+fn subsumes_components(broader: &[String], narrower: &[String]) -> bool {
+    match broader.first() {
+        None => narrower.is_empty(),
+        Some(component) if component == "**" => (0..=narrower.len())
+            .any(|skip| subsumes_components(&broader[1..], &narrower[skip..])),
+        Some(component) => {
+            !narrower.is_empty()
+                && component_contains(component, &narrower[0])
+                && subsumes_components(&broader[1..], &narrower[1..])
+        }
+    }
+}
+
+/// Whether `a` and `b` could both match the same path, handling `**` on
+/// either side the way `subsumes_components` handles it on `broader` alone.
+fn overlaps_components(a: &[String], b: &[String]) -> bool {
+    match (a.first(), b.first()) {
+        (None, None) => true,
+        (Some(component), _) if component == "**" => {
+            (0..=b.len()).any(|skip| overlaps_components(&a[1..], &b[skip..]))
+        }
+        (_, Some(component)) if component == "**" => {
+            (0..=a.len()).any(|skip| overlaps_components(&a[skip..], &b[1..]))
+        }
+        (None, Some(_)) | (Some(_), None) => false,
+        (Some(x), Some(y)) => component_overlaps(x, y) && overlaps_components(&a[1..], &b[1..]),
+    }
+}
+
+/// Whether some string exists that both glob components would accept.
+/// Character classes are treated as matching any single character rather
+/// than modelling class membership precisely (unlike `component_contains`,
+/// which checks classes exactly through `match_glob`), erring towards
+/// reporting an overlap rather than exact glob-vs-glob intersection.
+fn component_overlaps(a: &str, b: &str) -> bool {
+    overlaps_rec(&atoms(a), &atoms(b))
+}
+
+enum Atom {
+    Star,
+    Any,
+    Literal(char),
+}
+
+fn atoms(component: &str) -> Vec<Atom> {
+    let chars: Vec<char> = component.chars().collect();
+    let mut result = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' => {
+                result.push(Atom::Star);
+                i += 1;
+            }
+            '?' => {
+                result.push(Atom::Any);
+                i += 1;
+            }
+            '[' => match chars[i..].iter().position(|&c| c == ']') {
+                Some(rel) if rel > 0 => {
+                    result.push(Atom::Any);
+                    i += rel + 1;
+                }
+                _ => {
+                    result.push(Atom::Literal('['));
+                    i += 1;
+                }
+            },
+            '\\' if i + 1 < chars.len() => {
+                result.push(Atom::Literal(chars[i + 1]));
+                i += 2;
+            }
+            c => {
+                result.push(Atom::Literal(c));
+                i += 1;
+            }
+        }
+    }
+    result
+}
+
+fn overlaps_rec(a: &[Atom], b: &[Atom]) -> bool {
+    match (a.first(), b.first()) {
+        (None, None) => true,
+        (Some(Atom::Star), _) => {
+            overlaps_rec(&a[1..], b) || (!b.is_empty() && overlaps_rec(a, &b[1..]))
+        }
+        (_, Some(Atom::Star)) => {
+            overlaps_rec(a, &b[1..]) || (!a.is_empty() && overlaps_rec(&a[1..], b))
+        }
+        (None, Some(_)) | (Some(_), None) => false,
+        (Some(Atom::Literal(x)), Some(Atom::Literal(y))) => x == y && overlaps_rec(&a[1..], &b[1..]),
+        _ => overlaps_rec(&a[1..], &b[1..]),
+    }
+}
+
+/// Whether every string `match_glob` would accept for `narrower` is also
+/// accepted for `broader`. Exact equality and the bare `*` wildcard are
+/// always recognised; beyond that, containment is only checked when
+/// `narrower` itself has no wildcards of its own, since deciding
+/// glob-vs-glob containment in general isn't worth the complexity here.
+fn component_contains(broader: &str, narrower: &str) -> bool {
+    if broader == narrower || broader == "*" {
+        return true;
+    }
+    if narrower.chars().any(|c| matches!(c, '*' | '?' | '[')) {
+        return false;
+    }
+    match_glob(broader, narrower)
+}
+
+fn match_components(pattern: &[String], path: &[String]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(component) if component == "**" => {
+            match_components(&pattern[1..], path)
+                || (!path.is_empty() && match_components(pattern, &path[1..]))
+        }
+        Some(component) => {
+            !path.is_empty()
+                && match_glob(component, &path[0])
+                && match_components(&pattern[1..], &path[1..])
+        }
+    }
+}
+
+/// Matches a single path component (no `/`) against a glob component made
+/// of literal text, `*`, `?` and `[...]`/`[!...]` character classes.
+fn match_glob(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_rec(&pattern, &text)
+}
+
+fn glob_rec(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => glob_rec(&pattern[1..], text) || (!text.is_empty() && glob_rec(pattern, &text[1..])),
+        Some('?') => !text.is_empty() && glob_rec(&pattern[1..], &text[1..]),
+        Some('\\') if pattern.len() > 1 => {
+            !text.is_empty() && text[0] == pattern[1] && glob_rec(&pattern[2..], &text[1..])
+        }
+        Some('[') => match pattern.iter().position(|&c| c == ']') {
+            Some(close) if close > 0 => {
+                if text.is_empty() {
+                    return false;
+                }
+                let class = &pattern[1..close];
+                let (negated, class) = match class.first() {
+                    Some('!') => (true, &class[1..]),
+                    _ => (false, class),
+                };
+                if class_matches(class, text[0]) != negated {
+                    glob_rec(&pattern[close + 1..], &text[1..])
+                } else {
+                    false
+                }
+            }
+            _ => !text.is_empty() && text[0] == '[' && glob_rec(&pattern[1..], &text[1..]),
+        },
+        Some(c) => !text.is_empty() && text[0] == *c && glob_rec(&pattern[1..], &text[1..]),
+    }
+}
+
+fn class_matches(class: &[char], c: char) -> bool {
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == '-' {
+            if c >= class[i] && c <= class[i + 2] {
+                return true;
+            }
+            i += 3;
+        } else {
+            if class[i] == c {
+                return true;
+            }
+            i += 1;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser::parser;
+
+    fn rules_of(input: &str) -> Vec<IgnoreRule> {
+        let root = parser().parse(input).unwrap();
+        root.get_children()
+            .unwrap()
+            .iter()
+            .filter_map(IgnoreRule::compile)
+            .collect()
+    }
+
+    fn components(path: &str) -> Vec<String> {
+        path.split('/').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn it_matches_a_simple_wildcard_at_any_depth() {
+        let rules = rules_of("*.log");
+        assert!(rules[0].matches(&components("debug.log"), false));
+        assert!(rules[0].matches(&components("src/trace.log"), false));
+        assert!(!rules[0].matches(&components("src/main.rs"), false));
+    }
+
+    #[test]
+    fn it_anchors_patterns_with_a_non_trailing_slash() {
+        let rules = rules_of("build/output");
+        assert!(rules[0].matches(&components("build/output"), false));
+        assert!(!rules[0].matches(&components("nested/build/output"), false));
+    }
+
+    #[test]
+    fn it_matches_double_star_across_directories() {
+        let rules = rules_of("**/build/");
+        assert!(rules[0].matches(&components("a/b/build"), true));
+        assert!(!rules[0].matches(&components("a/b/other"), true));
+    }
+
+    #[test]
+    fn it_does_not_match_a_directory_only_rule_against_a_plain_file() {
+        let rules = rules_of("build/");
+        assert!(rules[0].matches(&components("build"), true));
+        assert!(!rules[0].matches(&components("build"), false));
+    }
+
+    #[test]
+    fn it_compiles_a_negated_rule() {
+        let rules = rules_of("!keep.log");
+        assert!(rules[0].negate);
+        assert!(rules[0].matches(&components("keep.log"), false));
+    }
+
+    #[test]
+    fn it_subsumes_a_literal_pattern_with_a_broader_wildcard() {
+        let [broader] = rules_of("*.log").try_into().unwrap();
+        let [narrower] = rules_of("debug.log").try_into().unwrap();
+        assert!(broader.subsumes(&narrower));
+        assert!(!narrower.subsumes(&broader));
+    }
+
+    #[test]
+    fn it_does_not_subsume_an_unrelated_pattern() {
+        let [broader] = rules_of("*.log").try_into().unwrap();
+        let [other] = rules_of("*.tmp").try_into().unwrap();
+        assert!(!broader.subsumes(&other));
+    }
+
+    #[test]
+    fn it_does_not_let_a_directory_only_rule_subsume_a_file_matching_rule() {
+        let [dir_only] = rules_of("build/").try_into().unwrap();
+        let [any_kind] = rules_of("build").try_into().unwrap();
+        assert!(!dir_only.subsumes(&any_kind));
+        assert!(any_kind.subsumes(&dir_only));
+    }
+
+    #[test]
+    fn it_subsumes_across_a_double_star() {
+        let [broader] = rules_of("**/build").try_into().unwrap();
+        let [narrower] = rules_of("a/b/build").try_into().unwrap();
+        assert!(broader.subsumes(&narrower));
+    }
+
+    #[test]
+    fn it_overlaps_two_patterns_that_neither_subsumes() {
+        let [a] = rules_of("*.log").try_into().unwrap();
+        let [b] = rules_of("important.*").try_into().unwrap();
+        assert!(a.overlaps(&b));
+        assert!(!a.subsumes(&b));
+        assert!(!b.subsumes(&a));
+    }
+
+    #[test]
+    fn it_does_not_overlap_unrelated_patterns() {
+        let [a] = rules_of("*.log").try_into().unwrap();
+        let [b] = rules_of("*.tmp").try_into().unwrap();
+        assert!(!a.overlaps(&b));
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use chumsky::span::SimpleSpan;
+use chumsky::Parser;
+
+use crate::parser::{line_parser, Token};
+
+/// A single line's cached parse: its content hash (to detect whether it needs
+/// reparsing) and its `Token`/errors with spans relative to the line start.
+#[derive(Debug, Clone)]
+pub struct LineParse {
+    hash: u64,
+    token: Token,
+    errors: Vec<LineError>,
+}
+
+/// An owned, rebaseable stand-in for a `Rich` parse error, since `Rich`
+/// borrows from the line it was parsed from and can't outlive a single
+/// `reparse` call.
+#[derive(Debug, Clone)]
+pub struct LineError {
+    pub span: SimpleSpan,
+    pub message: String,
+}
+
+pub struct ReparseResult {
+    pub token: Token,
+    pub errors: Vec<LineError>,
+}
+
+pub struct IncrementalParser;
+
+impl IncrementalParser {
+    /// Reparses only the lines of `text` whose content hash has changed since
+    /// `cache`, reusing every other line's cached `Token`/errors rebased by
+    /// the line's current byte offset, then splices the result into a single
+    /// `Token::File`. A line that fails to parse on its own falls back to an
+    /// empty `Path` for that line, since the full grammar's newline-recovery
+    /// can't be faithfully replicated in isolation.
+    pub fn reparse(text: &str, cache: &mut Vec<LineParse>) -> ReparseResult {
+        let lines: Vec<&str> = text.split('\n').collect();
+
+        let mut children = Vec::with_capacity(lines.len());
+        let mut errors = Vec::new();
+        let mut new_cache = Vec::with_capacity(lines.len());
+        let mut offset = 0usize;
+
+        for (i, line) in lines.iter().enumerate() {
+            // Strip a trailing `\r` before hashing/parsing: the full-file
+            // `parser()` never sees it either, since its `newline()`
+            // separator consumes a `\r\n` pair whole, but a plain `split('\n')`
+            // leaves it dangling on the end of every CRLF line, which the
+            // single-line grammar's `newline().not()` guards reject.
+            let content = line.strip_suffix('\r').unwrap_or(line);
+            let hash = hash_line(content);
+            let entry = match cache.get(i) {
+                Some(entry) if entry.hash == hash => entry.clone(),
+                _ => Self::parse_line(content, hash),
+            };
+
+            children.push(rebase(entry.token.clone(), offset));
+            errors.extend(entry.errors.iter().map(|error| LineError {
+                span: shift(error.span, offset),
+                message: error.message.clone(),
+            }));
+            new_cache.push(entry);
+
+            offset += line.len() + 1;
+        }
+
+        *cache = new_cache;
+
+        ReparseResult {
+            token: Token::File {
+                span: SimpleSpan::new(0, text.len()),
+                children,
+            },
+            errors,
+        }
+    }
+
+    fn parse_line(line: &str, hash: u64) -> LineParse {
+        let (output, line_errors) = line_parser().parse(line).into_output_errors();
+
+        let token = output.unwrap_or(Token::Path {
+            span: SimpleSpan::new(0, line.len()),
+            children: vec![],
+        });
+
+        let errors = line_errors
+            .iter()
+            .map(|error| LineError {
+                span: *error.span(),
+                message: error.to_string(),
+            })
+            .collect();
+
+        LineParse {
+            hash,
+            token,
+            errors,
+        }
+    }
+}
+
+fn hash_line(line: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn shift(span: SimpleSpan, delta: usize) -> SimpleSpan {
+    SimpleSpan::new(span.start + delta, span.end + delta)
+}
+
+fn rebase(token: Token, delta: usize) -> Token {
+    match token {
+        Token::File { span, children } => Token::File {
+            span: shift(span, delta),
+            children: children.into_iter().map(|child| rebase(child, delta)).collect(),
+        },
+        Token::Path { span, children } => Token::Path {
+            span: shift(span, delta),
+            children: children.into_iter().map(|child| rebase(child, delta)).collect(),
+        },
+        Token::Segment { span, value } => Token::Segment {
+            span: shift(span, delta),
+            value,
+        },
+        Token::Star { span } => Token::Star {
+            span: shift(span, delta),
+        },
+        Token::DoubleStar { span } => Token::DoubleStar {
+            span: shift(span, delta),
+        },
+        Token::AnyChar { span } => Token::AnyChar {
+            span: shift(span, delta),
+        },
+        Token::CharClass { span, negated, value } => Token::CharClass {
+            span: shift(span, delta),
+            negated,
+            value,
+        },
+        Token::Separator { span } => Token::Separator {
+            span: shift(span, delta),
+        },
+        Token::Negate { span } => Token::Negate {
+            span: shift(span, delta),
+        },
+        Token::Comment { span } => Token::Comment {
+            span: shift(span, delta),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser;
+
+    fn full_reparse(text: &str) -> Token {
+        parser().parse(text).unwrap()
+    }
+
+    #[test]
+    fn it_matches_a_full_reparse_on_the_first_pass() {
+        let mut cache = vec![];
+        let result = IncrementalParser::reparse("a/b\nc/d\n", &mut cache);
+        debug_assert_eq!(result.token, full_reparse("a/b\nc/d\n"));
+    }
+
+    #[test]
+    fn it_reuses_untouched_lines_and_matches_a_full_reparse() {
+        let mut cache = vec![];
+        IncrementalParser::reparse("a/b\nc/d\ne/f\n", &mut cache);
+
+        let edited = "a/b\nCHANGED\ne/f\n";
+        let result = IncrementalParser::reparse(edited, &mut cache);
+
+        debug_assert_eq!(result.token, full_reparse(edited));
+    }
+
+    #[test]
+    fn it_rebases_trailing_lines_after_a_shorter_edit_shifts_their_offsets() {
+        let mut cache = vec![];
+        IncrementalParser::reparse("alpha/beta\ngamma/delta\n", &mut cache);
+
+        let edited = "a\ngamma/delta\n";
+        let result = IncrementalParser::reparse(edited, &mut cache);
+
+        debug_assert_eq!(result.token, full_reparse(edited));
+    }
+
+    #[test]
+    fn it_matches_a_full_reparse_on_crlf_line_endings() {
+        let mut cache = vec![];
+        let text = "a/b\r\nc/d\r\n";
+        let result = IncrementalParser::reparse(text, &mut cache);
+        debug_assert_eq!(result.token, full_reparse(text));
+    }
+
+    #[test]
+    fn it_skips_reparsing_a_line_whose_hash_is_unchanged() {
+        let mut cache = vec![];
+        IncrementalParser::reparse("a/b\nc/d\n", &mut cache);
+        let first_line_before = cache[0].clone();
+
+        IncrementalParser::reparse("a/b\nCHANGED\n", &mut cache);
+
+        assert_eq!(cache[0].hash, first_line_before.hash);
+        assert_eq!(cache[0].token, first_line_before.token);
+    }
+}
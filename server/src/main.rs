@@ -1,32 +1,71 @@
-use crate::ast::AST;
-use crate::parser::parser;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::completions::{Completions, ResolvedCompletions};
+use crate::config::Config;
+use crate::diagnostics::Diagnostics;
+use crate::format::Formatter;
+use crate::hover::HoverProvider;
+use crate::ignore_rule::IgnoreRule;
+use crate::incremental::{IncrementalParser, LineParse};
+use crate::parser::{parser, Token};
+use crate::semantic_tokens::SemanticTokensProvider;
+use crate::source_map::LineIndex;
+use crate::suggestion::Suggestion;
 use chumsky::prelude::*;
 use dashmap::DashMap;
 use tokio::io::{stdin, stdout};
+use tokio::sync::Semaphore;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::{
-    CompletionOptions, CompletionParams, CompletionResponse, Diagnostic, DiagnosticSeverity,
-    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionProviderCapability,
+    CodeActionResponse, CompletionItem, CompletionOptions, CompletionParams, CompletionResponse,
+    DidChangeConfigurationParams, DidChangeTextDocumentParams, DidChangeWorkspaceFoldersParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DocumentFormattingParams,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverParams, HoverProviderCapability,
     InitializeParams, InitializeResult, InitializedParams, MessageType, OneOf, Position, Range,
-    ServerCapabilities, TextDocumentIdentifier, TextDocumentItem, TextDocumentSyncCapability,
-    TextDocumentSyncKind, VersionedTextDocumentIdentifier, WorkspaceFoldersServerCapabilities,
-    WorkspaceServerCapabilities,
+    SemanticTokens, SemanticTokensFullOptions, SemanticTokensOptions, SemanticTokensParams,
+    SemanticTokensResult, SemanticTokensServerCapabilities, ServerCapabilities,
+    TextDocumentIdentifier, TextDocumentItem, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextEdit, VersionedTextDocumentIdentifier, WorkspaceEdit, WorkspaceFolder,
+    WorkspaceFoldersServerCapabilities, WorkspaceServerCapabilities,
 };
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 use url::Url;
 
-use crate::workspace::Workspace;
+use crate::pattern_references::PatternReferences;
+use lsp_workspace::tree::Node;
+use lsp_workspace::workspace::Workspace;
 
-mod ast;
-mod file;
+mod completions;
+mod config;
+mod diagnostics;
+mod format;
+mod hover;
+mod ignore_rule;
+mod incremental;
+mod matcher;
 mod parser;
-mod workspace;
+mod pattern_references;
+mod semantic_tokens;
+mod source_map;
+mod suggestion;
+
+/// How many `.gitignore`-like files the workspace indexer reads concurrently
+/// while walking a workspace folder, so a large monorepo doesn't try to open
+/// thousands of file descriptors at once.
+const MAX_CONCURRENT_FILE_READS: usize = 8;
 
 #[derive(Debug)]
 struct Backend {
     client: Client,
     workspace: Workspace,
-    asts: DashMap<Url, AST>,
+    parse_trees: DashMap<Url, Token>,
+    line_caches: DashMap<Url, Vec<LineParse>>,
+    workspace_folders: Mutex<Vec<WorkspaceFolder>>,
+    resolved_completions: ResolvedCompletions,
+    config: Mutex<Config>,
 }
 
 impl Backend {
@@ -34,14 +73,28 @@ impl Backend {
         Self {
             client,
             workspace: Workspace::new(),
-            asts: DashMap::new(),
+            parse_trees: DashMap::new(),
+            line_caches: DashMap::new(),
+            workspace_folders: Mutex::new(vec![]),
+            resolved_completions: ResolvedCompletions::new(),
+            config: Mutex::new(Config::default()),
         }
     }
+
+    fn config(&self) -> Config {
+        self.config.lock().unwrap().clone()
+    }
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        if let Some(folders) = params.workspace_folders {
+            *self.workspace_folders.lock().unwrap() = folders;
+        }
+
+        *self.config.lock().unwrap() = Config::parse(params.initialization_options);
+
         let capabilities = ServerCapabilities {
             workspace: Some(WorkspaceServerCapabilities {
                 workspace_folders: Some(WorkspaceFoldersServerCapabilities {
@@ -52,11 +105,23 @@ impl LanguageServer for Backend {
             }),
             completion_provider: Some(CompletionOptions {
                 trigger_characters: Some(vec!["/".into()]),
+                resolve_provider: Some(true),
                 ..Default::default()
             }),
             text_document_sync: Some(TextDocumentSyncCapability::Kind(
                 TextDocumentSyncKind::INCREMENTAL,
             )),
+            document_formatting_provider: Some(OneOf::Left(true)),
+            code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            definition_provider: Some(OneOf::Left(true)),
+            semantic_tokens_provider: Some(
+                SemanticTokensServerCapabilities::SemanticTokensOptions(SemanticTokensOptions {
+                    legend: SemanticTokensProvider::legend(),
+                    full: Some(SemanticTokensFullOptions::Bool(true)),
+                    ..Default::default()
+                }),
+            ),
             ..Default::default()
         };
 
@@ -69,13 +134,54 @@ impl LanguageServer for Backend {
     async fn initialized(&self, _: InitializedParams) {
         self.client
             .log_message(MessageType::INFO, "server initialized successfully")
-            .await
+            .await;
+
+        let folders = self.workspace_folders.lock().unwrap().clone();
+        for folder in folders {
+            self.index_workspace_folder(folder.uri).await;
+        }
+    }
+
+    async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        self.workspace_folders
+            .lock()
+            .unwrap()
+            .retain(|folder| !params.event.removed.contains(folder));
+
+        for folder in &params.event.removed {
+            self.close_workspace_folder(&folder.uri);
+        }
+
+        for folder in params.event.added {
+            let uri = folder.uri.clone();
+            self.workspace_folders.lock().unwrap().push(folder);
+            self.index_workspace_folder(uri).await;
+        }
     }
 
     async fn shutdown(&self) -> Result<()> {
         Ok(())
     }
 
+    /// Replaces the whole config wholesale (clients are expected to send
+    /// their full settings object, not a patch) and re-runs `refresh_ast`
+    /// for every open document so diagnostics reflect the change
+    /// immediately instead of waiting for the next edit.
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        *self.config.lock().unwrap() = Config::parse(Some(params.settings));
+
+        let uris: Vec<Url> = self
+            .workspace
+            .files
+            .iter()
+            .filter_map(|entry| Url::parse(entry.key()).ok())
+            .collect();
+
+        for uri in uris {
+            self.refresh_ast(uri).await;
+        }
+    }
+
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
         let TextDocumentItem { uri, text, .. } = params.text_document;
         self.workspace.open(uri.clone(), text);
@@ -97,11 +203,183 @@ impl LanguageServer for Backend {
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
         let TextDocumentIdentifier { uri, .. } = params.text_document;
         self.workspace.close(&uri);
-        self.asts.remove(&uri);
+        self.parse_trees.remove(&uri);
+        self.line_caches.remove(&uri);
+    }
+
+    async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+
+        let file = match self.workspace.files.get(&uri.to_string()) {
+            Some(file) => file,
+            None => return Ok(None),
+        };
+
+        let offset = file.get_offset_at(position);
+        let root = match self.parse_trees.get(&uri) {
+            Some(root) => root.clone(),
+            None => return Ok(None),
+        };
+
+        let items =
+            Completions::generate(&self.workspace, &uri, &root, offset).unwrap_or_default();
+        Ok(Some(CompletionResponse::Array(items)))
+    }
+
+    /// Fills in a highlighted completion item's detail/documentation. Helix
+    /// (among other clients) can re-send a resolve request for the same
+    /// item on every render frame while it stays highlighted, so the result
+    /// is cached per `CompletionData` payload in `resolved_completions` and
+    /// only ever actually computed once.
+    async fn completion_resolve(&self, mut item: CompletionItem) -> Result<CompletionItem> {
+        Completions::resolve(
+            &mut item,
+            &self.config().completions.template_allow_list,
+            &self.resolved_completions,
+        );
+
+        Ok(item)
     }
 
-    async fn completion(&self, _params: CompletionParams) -> Result<Option<CompletionResponse>> {
-        Ok(Some(CompletionResponse::Array(vec![])))
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        let file = match self.workspace.files.get(&uri.to_string()) {
+            Some(file) => file,
+            None => return Ok(None),
+        };
+
+        let text = file.get_content();
+        let offset = file.get_offset_at(position);
+        let root = match self.parse_trees.get(&uri) {
+            Some(root) => root.clone(),
+            None => return Ok(None),
+        };
+
+        Ok(HoverProvider::hover(text.as_str(), &root, offset))
+    }
+
+    /// Resolves the pattern under the cursor to the workspace files it
+    /// currently matches, so "go to definition" on a rule jumps to what it
+    /// affects instead of just the line itself.
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        let file = match self.workspace.files.get(&uri.to_string()) {
+            Some(file) => file,
+            None => return Ok(None),
+        };
+
+        let offset = file.get_offset_at(position);
+        let root = match self.parse_trees.get(&uri) {
+            Some(root) => root.clone(),
+            None => return Ok(None),
+        };
+
+        let Some(gitignore_dir) = uri.to_file_path().ok().and_then(|path| path.parent().map(Path::to_path_buf))
+        else {
+            return Ok(None);
+        };
+
+        let limit = self.config().references.max_matches;
+        let tree = Node::read_tree(&gitignore_dir).unwrap_or(Node::Dir {
+            children: Default::default(),
+        });
+        let locations = PatternReferences::resolve(&root, offset, &gitignore_dir, &tree, limit);
+
+        Ok(locations.map(GotoDefinitionResponse::Array))
+    }
+
+    async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        let uri = params.text_document.uri;
+        let file = match self.workspace.files.get(&uri.to_string()) {
+            Some(file) => file,
+            None => return Ok(None),
+        };
+
+        let text = file.get_content();
+        let root = match self.parse_trees.get(&uri) {
+            Some(root) => root.clone(),
+            None => return Ok(None),
+        };
+
+        let formatted = Formatter::format(text.as_str(), &root, self.config().format);
+        if formatted == text {
+            return Ok(None);
+        }
+
+        let line_index = LineIndex::new(text.as_str());
+        let end = line_index.position(text.as_str(), text.len());
+        Ok(Some(vec![TextEdit {
+            range: Range::new(Position::new(0, 0), end),
+            new_text: formatted,
+        }]))
+    }
+
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        let uri = params.text_document.uri;
+        let file = match self.workspace.files.get(&uri.to_string()) {
+            Some(file) => file,
+            None => return Ok(None),
+        };
+
+        let text = file.get_content();
+        let root = match self.parse_trees.get(&uri) {
+            Some(root) => root.clone(),
+            None => return Ok(None),
+        };
+
+        let line_index = LineIndex::new(text.as_str());
+        let data = SemanticTokensProvider::tokens(text.as_str(), &root, &line_index);
+
+        Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+            result_id: None,
+            data,
+        })))
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+
+        let actions = params
+            .context
+            .diagnostics
+            .into_iter()
+            .filter_map(|diagnostic| {
+                let suggestion: Suggestion = serde_json::from_value(diagnostic.data.clone()?).ok()?;
+
+                let mut changes = HashMap::new();
+                changes.insert(
+                    uri.clone(),
+                    vec![TextEdit {
+                        range: suggestion.range,
+                        new_text: suggestion.replacement.clone(),
+                    }],
+                );
+
+                Some(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: format!("Replace with `{}`", suggestion.replacement),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic]),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(changes),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }))
+            })
+            .collect();
+
+        Ok(Some(actions))
     }
 }
 
@@ -119,39 +397,183 @@ impl Backend {
         };
 
         let text = file.get_content();
+        let line_index = LineIndex::new(text.as_str());
 
-        let parser = parser();
-        let (out, err) = parser.parse(text.as_str()).into_output_errors();
-
-        let no_errors = err.is_empty();
-        let errors = err.into_iter();
-
-        for error in errors {
-            let span = error.span();
-            let position = Position::new(span.start as u32, span.end as u32);
-            let diagnostic = Diagnostic::new(
-                Range::new(position, position),
-                Some(DiagnosticSeverity::ERROR),
-                None,
-                Some("Gitignore Ultimate".to_string()),
-                error.to_string(),
-                None,
-                None,
-            );
-            self.client
-                .publish_diagnostics(uri.clone(), vec![diagnostic], None)
-                .await;
+        let mut cache = self.line_caches.entry(uri.clone()).or_default();
+        let result = IncrementalParser::reparse(text.as_str(), &mut *cache);
+        drop(cache);
+
+        let diagnostics = Diagnostics::generate(
+            &result,
+            text.as_str(),
+            &line_index,
+            &uri,
+            self.config().diagnostics,
+        );
+        self.client
+            .publish_diagnostics(uri.clone(), diagnostics, None)
+            .await;
+
+        self.parse_trees.insert(uri, result.token.clone());
+    }
+
+    /// Walks `root` (a workspace folder), opening every `.gitignore`,
+    /// `.git/info/exclude` and `*.gitignore` file it finds so cross-file
+    /// features work without the user opening each file first. Borrows
+    /// Deno's LSP startup approach: a `VecDeque` of directories still to
+    /// visit instead of recursion, so one workspace folder can't blow the
+    /// stack. Directories already excluded by an ancestor `.gitignore` are
+    /// never queued, since nothing inside them can affect the result.
+    async fn index_workspace_folder(&self, root: Url) {
+        let Ok(root) = root.to_file_path() else {
+            return;
+        };
+
+        let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FILE_READS));
+        let mut dirs = VecDeque::from([(root, Vec::<(PathBuf, IgnoreRule)>::new())]);
+
+        while let Some((dir, mut rules)) = dirs.pop_front() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+
+            let mut subdirs = vec![];
+            let mut ignore_files = vec![];
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let path = entry.path();
+                let is_dir = entry.file_type().await.map(|kind| kind.is_dir()).unwrap_or(false);
+                let name = entry.file_name().to_string_lossy().into_owned();
+
+                if is_dir {
+                    subdirs.push((path, name));
+                } else if name == ".gitignore" || name.ends_with(".gitignore") {
+                    ignore_files.push(path);
+                }
+            }
+
+            // Kick off every ignore file's read as its own task before
+            // awaiting any of them, so they actually overlap on I/O instead
+            // of running one at a time; `read_to_string_bounded` still caps
+            // how many are in flight across the whole indexer.
+            let reads: Vec<_> = ignore_files
+                .into_iter()
+                .map(|path| {
+                    let semaphore = semaphore.clone();
+                    tokio::spawn(async move {
+                        let text = Self::read_to_string_bounded(&semaphore, &path).await;
+                        (path, text)
+                    })
+                })
+                .collect();
+
+            for read in reads {
+                let Ok((path, Some(text))) = read.await else {
+                    continue;
+                };
+                self.index_ignore_file(path, &text, &dir, &mut rules).await;
+            }
+
+            for (path, name) in subdirs {
+                if name == ".git" {
+                    let exclude = path.join("info").join("exclude");
+                    if let Some(text) = Self::read_to_string_bounded(&semaphore, &exclude).await {
+                        self.index_ignore_file(exclude, &text, &path, &mut rules.clone()).await;
+                    }
+                    continue;
+                }
+
+                if Self::is_ignored(&path, &rules, true) {
+                    continue;
+                }
+
+                dirs.push_back((path, rules.clone()));
+            }
         }
+    }
+
+    /// Whether the last rule (in file order) among `rules` that matches
+    /// `path` is a plain ignore rather than a negation, mirroring gitignore
+    /// precedence: later rules override earlier ones. `is_dir` says whether
+    /// `path` itself is a directory, since a directory-only rule must never
+    /// match a plain file.
+    fn is_ignored(path: &std::path::Path, rules: &[(PathBuf, IgnoreRule)], is_dir: bool) -> bool {
+        rules.iter().fold(false, |ignored, (base, rule)| match path.strip_prefix(base) {
+            Ok(relative) => {
+                let components = Self::path_components(relative);
+                if rule.matches(&components, is_dir) {
+                    !rule.negate
+                } else {
+                    ignored
+                }
+            }
+            Err(_) => ignored,
+        })
+    }
+
+    /// Parses one discovered ignore file, opens it in the workspace so it
+    /// gets diagnostics like any other document, and compiles its rules
+    /// (based at `dir`) into `rules` for the directories below it. Uses
+    /// `open_if_absent` rather than `open`, since `did_open` can race this
+    /// background walk for a file the client already has open with unsaved
+    /// edits; `refresh_ast` still re-derives diagnostics from whatever the
+    /// workspace actually holds for `uri`, so it stays correct either way.
+    async fn index_ignore_file(
+        &self,
+        path: PathBuf,
+        text: &str,
+        dir: &std::path::Path,
+        rules: &mut Vec<(PathBuf, IgnoreRule)>,
+    ) {
+        let Ok(uri) = Url::from_file_path(&path) else {
+            return;
+        };
+
+        self.workspace.open_if_absent(uri.clone(), text.to_string());
+        self.refresh_ast(uri).await;
 
-        if no_errors {
-            self.client
-                .publish_diagnostics(uri.clone(), vec![], None)
-                .await;
+        if let Some(root) = parser().parse(text).into_output() {
+            if let Some(children) = root.get_children() {
+                rules.extend(
+                    children
+                        .iter()
+                        .filter_map(IgnoreRule::compile)
+                        .map(|rule| (dir.to_path_buf(), rule)),
+                );
+            }
         }
+    }
+
+    async fn read_to_string_bounded(semaphore: &Semaphore, path: &std::path::Path) -> Option<String> {
+        let _permit = semaphore.acquire().await.ok()?;
+        tokio::fs::read_to_string(path).await.ok()
+    }
+
+    fn path_components(path: &std::path::Path) -> Vec<String> {
+        path.components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect()
+    }
 
-        if out.is_some() {
-            let ast = AST::parse(out.unwrap());
-            self.asts.insert(uri, ast);
+    /// Closes every open document under a removed workspace folder, undoing
+    /// what `index_workspace_folder` opened for it.
+    fn close_workspace_folder(&self, root: &Url) {
+        let Ok(root) = root.to_file_path() else {
+            return;
+        };
+
+        let uris: Vec<Url> = self
+            .workspace
+            .files
+            .iter()
+            .filter_map(|entry| Url::parse(entry.key()).ok())
+            .filter(|uri| uri.to_file_path().map(|path| path.starts_with(&root)).unwrap_or(false))
+            .collect();
+
+        for uri in uris {
+            self.workspace.close(&uri);
+            self.parse_trees.remove(&uri);
+            self.line_caches.remove(&uri);
         }
     }
 }
@@ -164,3 +586,43 @@ async fn main() {
     let (service, socket) = LspService::new(Backend::new);
     Server::new(stdin, stdout, socket).serve(service).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser::parser;
+
+    fn rule(base: &str, pattern: &str) -> (PathBuf, IgnoreRule) {
+        let root = parser().parse(pattern).unwrap();
+        let line = root.get_children().unwrap().iter().next().unwrap().clone();
+        (PathBuf::from(base), IgnoreRule::compile(&line).unwrap())
+    }
+
+    #[test]
+    fn it_ignores_a_path_matched_by_the_last_applicable_rule_in_file_order() {
+        let rules = vec![rule("/repo", "*.log"), rule("/repo", "!keep.log")];
+        assert!(Backend::is_ignored(Path::new("/repo/debug.log"), &rules, false));
+        assert!(!Backend::is_ignored(Path::new("/repo/keep.log"), &rules, false));
+    }
+
+    #[test]
+    fn it_does_not_ignore_a_path_outside_any_rules_base() {
+        let rules = vec![rule("/repo/nested", "*.log")];
+        assert!(!Backend::is_ignored(Path::new("/repo/other/debug.log"), &rules, false));
+    }
+
+    #[test]
+    fn it_only_lets_a_directory_only_rule_skip_an_actual_directory() {
+        let rules = vec![rule("/repo", "build/")];
+        assert!(Backend::is_ignored(Path::new("/repo/build"), &rules, true));
+        assert!(!Backend::is_ignored(Path::new("/repo/build"), &rules, false));
+    }
+
+    #[test]
+    fn it_splits_a_path_into_its_string_components() {
+        let components = Backend::path_components(Path::new("src/lib.rs"));
+        assert_eq!(components, vec!["src".to_string(), "lib.rs".to_string()]);
+    }
+}
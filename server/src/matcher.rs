@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use lsp_workspace::tree::Node;
+
+use crate::ignore_rule::IgnoreRule;
+
+/// The outcome of running every rule in a `.gitignore` over the directory
+/// tree next to it: which tracked paths end up ignored, and which rule is
+/// currently deciding the status of each of them.
+#[derive(Debug, Default)]
+pub struct MatchResult {
+    pub ignored: HashSet<PathBuf>,
+    /// Indexed the same way as the `rules` slice passed to `evaluate`.
+    pub rule_matches: Vec<Vec<PathBuf>>,
+}
+
+/// Walks a real directory tree applying a `.gitignore`'s rules the way git
+/// actually does: last match (in file order) wins, but once a directory is
+/// excluded, git never looks inside it again, so nothing nested below it
+/// can be re-included by a later negation, however specifically it targets
+/// that file.
+pub struct Matcher;
+
+impl Matcher {
+    /// Evaluates `rules` (in file order) against every entry in `tree`.
+    pub fn evaluate(tree: &Node, rules: &[IgnoreRule]) -> MatchResult {
+        let mut result = MatchResult {
+            ignored: HashSet::new(),
+            rule_matches: vec![Vec::new(); rules.len()],
+        };
+
+        let mut prefix = vec![];
+        Self::walk(tree, &mut prefix, false, rules, &mut result);
+        result
+    }
+
+    fn walk(
+        node: &Node,
+        prefix: &mut Vec<String>,
+        parent_ignored: bool,
+        rules: &[IgnoreRule],
+        result: &mut MatchResult,
+    ) {
+        let children = match node.children() {
+            Some(children) => children,
+            None => return,
+        };
+
+        for (name, child) in children {
+            prefix.push(name.clone());
+            let is_dir = child.is_dir();
+
+            let mut ignored = parent_ignored;
+            let mut deciding_rule = None;
+
+            // `rule.matches` already skips a directory-only rule against a
+            // plain file on its own, so there is nothing extra to check
+            // here beyond the ancestor-exclusion short-circuit below.
+            if !parent_ignored {
+                for (index, rule) in rules.iter().enumerate() {
+                    if rule.matches(prefix, is_dir) {
+                        ignored = !rule.negate;
+                        deciding_rule = Some(index);
+                    }
+                }
+            }
+
+            let path: PathBuf = prefix.iter().collect();
+            if ignored {
+                result.ignored.insert(path.clone());
+            }
+            if let Some(index) = deciding_rule {
+                result.rule_matches[index].push(path);
+            }
+
+            if is_dir {
+                Self::walk(child, prefix, ignored, rules, result);
+            }
+
+            prefix.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser::parser;
+
+    fn rules_of(input: &str) -> Vec<IgnoreRule> {
+        let root = parser().parse(input).unwrap();
+        root.get_children()
+            .unwrap()
+            .iter()
+            .filter_map(IgnoreRule::compile)
+            .collect()
+    }
+
+    fn file(name: &str) -> (String, Node) {
+        (name.to_string(), Node::File { size: 0 })
+    }
+
+    fn dir(name: &str, children: Vec<(String, Node)>) -> (String, Node) {
+        (
+            name.to_string(),
+            Node::Dir {
+                children: children.into_iter().collect(),
+            },
+        )
+    }
+
+    fn tree(children: Vec<(String, Node)>) -> Node {
+        Node::Dir {
+            children: children.into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn it_matches_a_simple_wildcard_at_any_depth() {
+        let rules = rules_of("*.log");
+        let tree = tree(vec![
+            file("debug.log"),
+            dir("src", vec![file("main.rs"), file("trace.log")]),
+        ]);
+
+        let result = Matcher::evaluate(&tree, &rules);
+        assert!(result.ignored.contains(&PathBuf::from("debug.log")));
+        assert!(result.ignored.contains(&PathBuf::from("src/trace.log")));
+        assert!(!result.ignored.contains(&PathBuf::from("src/main.rs")));
+    }
+
+    #[test]
+    fn it_anchors_patterns_with_a_non_trailing_slash() {
+        let rules = rules_of("build/output");
+        let tree = tree(vec![
+            dir("build", vec![file("output")]),
+            dir("nested", vec![dir("build", vec![file("output")])]),
+        ]);
+
+        let result = Matcher::evaluate(&tree, &rules);
+        assert!(result.ignored.contains(&PathBuf::from("build/output")));
+        assert!(!result.ignored.contains(&PathBuf::from("nested/build/output")));
+    }
+
+    #[test]
+    fn it_matches_double_star_across_directories() {
+        let rules = rules_of("**/build/");
+        let tree = tree(vec![dir(
+            "a",
+            vec![dir("b", vec![dir("build", vec![file("out")])])],
+        )]);
+
+        let result = Matcher::evaluate(&tree, &rules);
+        assert!(result.ignored.contains(&PathBuf::from("a/b/build")));
+        assert!(result.ignored.contains(&PathBuf::from("a/b/build/out")));
+    }
+
+    #[test]
+    fn it_reincludes_with_a_negated_rule() {
+        let rules = rules_of("*.log\n!keep.log");
+        let tree = tree(vec![file("debug.log"), file("keep.log")]);
+
+        let result = Matcher::evaluate(&tree, &rules);
+        assert!(result.ignored.contains(&PathBuf::from("debug.log")));
+        assert!(!result.ignored.contains(&PathBuf::from("keep.log")));
+    }
+
+    #[test]
+    fn it_cannot_reinclude_a_file_inside_an_excluded_directory() {
+        let rules = rules_of("build/\n!build/keep");
+        let tree = tree(vec![dir("build", vec![file("keep")])]);
+
+        let result = Matcher::evaluate(&tree, &rules);
+        assert!(result.ignored.contains(&PathBuf::from("build")));
+        assert!(result.ignored.contains(&PathBuf::from("build/keep")));
+        assert_eq!(result.rule_matches[1].len(), 0);
+    }
+
+    #[test]
+    fn it_matches_directories_only_for_trailing_slash_patterns() {
+        let rules = rules_of("logs/");
+        let tree = tree(vec![file("logs"), dir("logs2", vec![])]);
+
+        let result = Matcher::evaluate(&tree, &rules);
+        assert!(!result.ignored.contains(&PathBuf::from("logs")));
+    }
+}
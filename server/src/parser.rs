@@ -1,5 +1,6 @@
 use chumsky::prelude::{any, end, just, skip_then_retry_until, Rich};
 use chumsky::primitive::choice;
+use chumsky::span::SimpleSpan;
 use chumsky::text::newline;
 use chumsky::{extra, IterParser, Parser};
 
@@ -7,31 +8,244 @@ use crate::parser::Token::{Comment, Path};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
-    File(Vec<Token>),
-    Path(Vec<Token>),
-    Segment(String),
-    Separator,
-    Negate,
-    Comment,
+    File {
+        span: SimpleSpan,
+        children: Vec<Token>,
+    },
+    Path {
+        span: SimpleSpan,
+        children: Vec<Token>,
+    },
+    Segment {
+        span: SimpleSpan,
+        value: String,
+    },
+    /// A single `*`, matching any run of non-`/` characters.
+    Star {
+        span: SimpleSpan,
+    },
+    /// A whole path component that is exactly `**`, spanning directory
+    /// boundaries.
+    DoubleStar {
+        span: SimpleSpan,
+    },
+    /// A single `?`, matching any one non-`/` character.
+    AnyChar {
+        span: SimpleSpan,
+    },
+    /// A `[...]`/`[!...]` character class. `negated` is set for `[!...]`,
+    /// `value` holds the raw text between the brackets (or up to the end of
+    /// the line, if the class is missing its closing `]`).
+    CharClass {
+        span: SimpleSpan,
+        negated: bool,
+        value: String,
+    },
+    Separator {
+        span: SimpleSpan,
+    },
+    Negate {
+        span: SimpleSpan,
+    },
+    Comment {
+        span: SimpleSpan,
+    },
 }
 
-pub fn parser<'a>() -> impl Parser<'a, &'a str, Token, extra::Err<Rich<'a, char>>> {
+impl Token {
+    pub fn get_children(&self) -> Option<&Vec<Token>> {
+        match self {
+            Token::File { children, .. } => Some(children),
+            Token::Path { children, .. } => Some(children),
+            _ => None,
+        }
+    }
+
+    pub fn get_span(&self) -> &SimpleSpan {
+        match self {
+            Token::File { span, .. } => span,
+            Token::Path { span, .. } => span,
+            Token::Segment { span, .. } => span,
+            Token::Star { span, .. } => span,
+            Token::DoubleStar { span, .. } => span,
+            Token::AnyChar { span, .. } => span,
+            Token::CharClass { span, .. } => span,
+            Token::Separator { span, .. } => span,
+            Token::Negate { span, .. } => span,
+            Token::Comment { span, .. } => span,
+        }
+    }
+
+    /// Collects every descendant (at any depth) for which `filter` returns
+    /// `true`, in document order, by walking `File`/`Path` children
+    /// recursively. Leaf tokens have no children of their own to recurse
+    /// into, so they only ever contribute themselves.
+    pub fn get_all_children_filtered<F>(&self, filter: &F) -> Vec<&Token>
+    where
+        F: Fn(&Token) -> bool,
+    {
+        let mut results = vec![];
+        if let Some(children) = self.get_children() {
+            for child in children {
+                if filter(child) {
+                    results.push(child);
+                }
+                results.extend(child.get_all_children_filtered(filter));
+            }
+        }
+        results
+    }
+
+    /// Whether `offset` falls within this token's span, inclusive of both
+    /// ends so a cursor sitting right after the last character of a line
+    /// still resolves to it.
+    pub fn contains_offset(&self, offset: usize) -> bool {
+        let span = self.get_span();
+        (span.start..=span.end).contains(&offset)
+    }
+
+    /// Splits a `Path`'s children into its negation flag, directory-only
+    /// flag, and the remaining content tokens that make up the pattern
+    /// itself (wildcards, segments, and the separators between them), or
+    /// `None` if there is no matchable content (e.g. a blank line, or a
+    /// bare `/` or `!`). `self` must be a `Path` token.
+    pub fn decompose_path(&self) -> Option<(bool, bool, &[Token])> {
+        let children = self.get_children()?;
+
+        let negate = matches!(children.first(), Some(Token::Negate { .. }));
+        let content = &children[if negate { 1 } else { 0 }..];
+        if content.is_empty() {
+            return None;
+        }
+
+        let dir_only = matches!(content.last(), Some(Token::Separator { .. }));
+        let content = if dir_only {
+            &content[..content.len() - 1]
+        } else {
+            content
+        };
+        if content.is_empty() {
+            return None;
+        }
+
+        Some((negate, dir_only, content))
+    }
+}
+
+/// Collapses a path component that turned out to be exactly two adjacent `*`
+/// stars into a single `DoubleStar` token, so `**` is visible to the rest of
+/// the server as one node instead of two stars that happen to be next to
+/// each other. A `**` next to other characters in the same component (e.g.
+/// `a**b`) is left as two plain `Star` tokens, since gitignore only gives
+/// `**` its directory-spanning meaning when it is a whole path component.
+fn collapse_double_star(tokens: Vec<Token>) -> Vec<Token> {
+    match tokens.as_slice() {
+        [Token::Star { span: a }, Token::Star { span: b }] if a.end == b.start => {
+            vec![Token::DoubleStar {
+                span: SimpleSpan::new(a.start, b.end),
+            }]
+        }
+        _ => tokens,
+    }
+}
+
+/// Trims trailing whitespace from `value`, except a single trailing space
+/// that is itself escaped (`\ `). Whether the whitespace is escaped depends
+/// on the parity of the run of backslashes right before it: `\ ` escapes the
+/// space, but `\\ ` is an escaped backslash followed by a plain space that
+/// should still be trimmed. Shared with `format.rs`, which trims the same
+/// way when rendering a canonical line.
+pub(crate) fn trim_trailing_unescaped_whitespace(value: &str) -> &str {
+    let bytes = value.as_bytes();
+    let mut end = value.len();
+    while end > 0 && bytes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+
+    let mut backslashes = 0;
+    while end > backslashes && bytes[end - 1 - backslashes] == b'\\' {
+        backslashes += 1;
+    }
+
+    if end < value.len() && backslashes % 2 == 1 {
+        end += 1;
+    }
+    &value[..end]
+}
+
+fn line<'a>() -> impl Parser<'a, &'a str, Token, extra::Err<Rich<'a, char>>> {
     let comment = just("#")
         .then(any().and_is(newline().not()).and_is(end().not()).repeated())
-        .map(|_| Comment);
+        .map_with(|_, e| Comment { span: e.span() });
 
-    let segment = any()
-        .and_is(just("/").not())
+    // A lone trailing `\` (nothing left on the line to escape) is kept as a
+    // literal backslash rather than failing to parse, matching how a plain
+    // segment handled it before wildcards needed their own escaping.
+    let escaped_char = just('\\')
+        .then(any().and_is(newline().not()).or_not())
+        .map_with(|(backslash, c), e| {
+            let value = match c {
+                Some(c) => format!("{backslash}{c}"),
+                None => backslash.to_string(),
+            };
+            (value, e.span())
+        });
+
+    let literal_char = any()
+        .and_is(just('/').not())
         .and_is(newline().not())
         .and_is(end().not())
+        .and_is(just('*').not())
+        .and_is(just('?').not())
+        .and_is(just('[').not())
+        .and_is(just('\\').not())
+        .map_with(|c: char, e| (c.to_string(), e.span()));
+
+    let literal = choice((escaped_char, literal_char))
+        .repeated()
+        .at_least(1)
+        .collect::<Vec<_>>()
+        .map_with(|parts, e| {
+            let value: String = parts.into_iter().map(|(value, _)| value).collect();
+            let value = trim_trailing_unescaped_whitespace(value.trim_start());
+            Token::Segment {
+                span: e.span(),
+                value: value.to_string(),
+            }
+        });
+
+    let star = just('*').map_with(|_, e| Token::Star { span: e.span() });
+    let any_char = just('?').map_with(|_, e| Token::AnyChar { span: e.span() });
+
+    // The closing `]` is optional so an unterminated class (e.g. `[a-z`
+    // trailing off the end of the line) still produces a `CharClass` token
+    // instead of failing the whole line to parse; `Diagnostics` is the one
+    // that flags the missing bracket.
+    let char_class = just('[')
+        .ignore_then(just('!').or_not())
+        .then(
+            any()
+                .and_is(just(']').not())
+                .and_is(newline().not())
+                .and_is(end().not())
+                .repeated()
+                .collect::<String>(),
+        )
+        .then_ignore(just(']').or_not())
+        .map_with(|(negated, value), e| Token::CharClass {
+            span: e.span(),
+            negated: negated.is_some(),
+            value,
+        });
+
+    let segment = choice((char_class, star, any_char, literal))
         .repeated()
         .at_least(1)
-        .collect::<String>()
-        .map(|x| x.trim().to_string())
-        .map(|x| Token::Segment(x));
+        .collect::<Vec<_>>()
+        .map(collapse_double_star);
 
-    let negate = just("!").map(|_| Token::Negate);
-    let separator = just("/").map(|_| Token::Separator);
+    let negate = just("!").map_with(|_, e| Token::Negate { span: e.span() });
+    let separator = just("/").map_with(|_, e| Token::Separator { span: e.span() });
 
     let path = negate
         .or_not()
@@ -44,13 +258,16 @@ pub fn parser<'a>() -> impl Parser<'a, &'a str, Token, extra::Err<Rich<'a, char>
                 .collect::<Vec<_>>()
                 .map(|x| {
                     x.into_iter()
-                        .map(|(a, b)| vec![a, b])
-                        .flatten()
+                        .flat_map(|(separator, segment)| {
+                            let mut tokens = vec![separator];
+                            tokens.extend(segment);
+                            tokens
+                        })
                         .collect::<Vec<_>>()
                 }),
         )
         .then(separator.or_not())
-        .map(|((((a, b), c), d), e)| {
+        .map_with(|((((a, b), c), d), e), ext| {
             let mut path = vec![];
             if let Some(a) = a {
                 path.push(a);
@@ -59,46 +276,98 @@ pub fn parser<'a>() -> impl Parser<'a, &'a str, Token, extra::Err<Rich<'a, char>
                 path.push(b);
             }
             if let Some(c) = c {
-                path.push(c);
+                path.extend(c);
             }
             path.extend(d);
             if let Some(e) = e {
                 path.push(e);
             }
-            Path(path)
+            Path {
+                span: ext.span(),
+                children: path,
+            }
         });
 
-    let lines = choice((comment, path))
+    choice((comment, path))
+}
+
+/// Parses a single line's content in isolation, with spans relative to the
+/// start of that line rather than the whole file. Used by the per-line cache
+/// in `incremental.rs` to reparse only the lines that changed.
+pub fn line_parser<'a>() -> impl Parser<'a, &'a str, Token, extra::Err<Rich<'a, char>>> {
+    line()
+}
+
+pub fn parser<'a>() -> impl Parser<'a, &'a str, Token, extra::Err<Rich<'a, char>>> {
+    line()
         .separated_by(newline().recover_with(skip_then_retry_until(
             any().ignored(),
             newline().ignored().or(end()).ignored(),
         )))
         .collect::<Vec<_>>()
-        .map(Token::File);
-
-    lines
+        .map_with(|children, e| Token::File {
+            span: e.span(),
+            children,
+        })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_line_parser_matches_the_path_branch_of_the_full_parser() {
+        let full = parser().parse("a/b").unwrap();
+        let children = full.get_children().unwrap();
+        let line = line_parser().parse("a/b").unwrap();
+        assert_eq!(&line, &children[0]);
+    }
+
     #[test]
     fn test_path() {
         let tree = parser().parse("a/b/c/d/e").unwrap();
         assert_eq!(
             tree,
-            Token::File(vec![Token::Path(vec![
-                Token::Segment("a".to_string()),
-                Token::Separator,
-                Token::Segment("b".to_string()),
-                Token::Separator,
-                Token::Segment("c".to_string()),
-                Token::Separator,
-                Token::Segment("d".to_string()),
-                Token::Separator,
-                Token::Segment("e".to_string())
-            ])])
+            Token::File {
+                span: SimpleSpan::new(0, 9),
+                children: vec![Token::Path {
+                    span: SimpleSpan::new(0, 9),
+                    children: vec![
+                        Token::Segment {
+                            span: SimpleSpan::new(0, 1),
+                            value: "a".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(1, 2)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(2, 3),
+                            value: "b".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(3, 4)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(4, 5),
+                            value: "c".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(5, 6)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(6, 7),
+                            value: "d".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(7, 8)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(8, 9),
+                            value: "e".to_string(),
+                        },
+                    ]
+                }]
+            }
         );
     }
 
@@ -107,15 +376,38 @@ mod tests {
         let tree = parser().parse("/a/b/c/").unwrap();
         assert_eq!(
             tree,
-            Token::File(vec![Token::Path(vec![
-                Token::Separator,
-                Token::Segment("a".to_string()),
-                Token::Separator,
-                Token::Segment("b".to_string()),
-                Token::Separator,
-                Token::Segment("c".to_string()),
-                Token::Separator
-            ])])
+            Token::File {
+                span: SimpleSpan::new(0, 7),
+                children: vec![Token::Path {
+                    span: SimpleSpan::new(0, 7),
+                    children: vec![
+                        Token::Separator {
+                            span: SimpleSpan::new(0, 1)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(1, 2),
+                            value: "a".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(2, 3)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(3, 4),
+                            value: "b".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(4, 5)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(5, 6),
+                            value: "c".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(6, 7)
+                        },
+                    ]
+                }]
+            }
         );
     }
 
@@ -124,14 +416,38 @@ mod tests {
         let tree = parser().parse("\n\n").unwrap();
         assert_eq!(
             tree,
-            Token::File(vec![Path(vec![]), Path(vec![]), Path(vec![])])
+            Token::File {
+                span: SimpleSpan::new(0, 2),
+                children: vec![
+                    Path {
+                        span: SimpleSpan::new(0, 0),
+                        children: vec![]
+                    },
+                    Path {
+                        span: SimpleSpan::new(1, 1),
+                        children: vec![]
+                    },
+                    Path {
+                        span: SimpleSpan::new(2, 2),
+                        children: vec![]
+                    }
+                ]
+            }
         );
     }
 
     #[test]
     fn test_comment() {
         let tree = parser().parse("# a comment").unwrap();
-        assert_eq!(tree, Token::File(vec![Comment]));
+        assert_eq!(
+            tree,
+            Token::File {
+                span: SimpleSpan::new(0, 11),
+                children: vec![Comment {
+                    span: SimpleSpan::new(0, 11)
+                }]
+            }
+        );
     }
 
     #[test]
@@ -139,13 +455,32 @@ mod tests {
         let tree = parser().parse("\\#not/a/comment").unwrap();
         assert_eq!(
             tree,
-            Token::File(vec![Path(vec![
-                Token::Segment("\\#not".to_string()),
-                Token::Separator,
-                Token::Segment("a".to_string()),
-                Token::Separator,
-                Token::Segment("comment".to_string())
-            ])])
+            Token::File {
+                span: SimpleSpan::new(0, 15),
+                children: vec![Path {
+                    span: SimpleSpan::new(0, 15),
+                    children: vec![
+                        Token::Segment {
+                            span: SimpleSpan::new(0, 5),
+                            value: "\\#not".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(5, 6)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(6, 7),
+                            value: "a".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(7, 8)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(8, 15),
+                            value: "comment".to_string(),
+                        },
+                    ]
+                }]
+            }
         );
     }
 
@@ -160,14 +495,35 @@ mod tests {
         let tree = parser().parse("!a/b/c").unwrap();
         assert_eq!(
             tree,
-            Token::File(vec![Path(vec![
-                Token::Negate,
-                Token::Segment("a".to_string()),
-                Token::Separator,
-                Token::Segment("b".to_string()),
-                Token::Separator,
-                Token::Segment("c".to_string())
-            ])])
+            Token::File {
+                span: SimpleSpan::new(0, 6),
+                children: vec![Path {
+                    span: SimpleSpan::new(0, 6),
+                    children: vec![
+                        Token::Negate {
+                            span: SimpleSpan::new(0, 1)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(1, 2),
+                            value: "a".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(2, 3)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(3, 4),
+                            value: "b".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(4, 5)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(5, 6),
+                            value: "c".to_string(),
+                        },
+                    ]
+                }]
+            }
         );
     }
 
@@ -176,13 +532,234 @@ mod tests {
         let tree = parser().parse("\\!a/b/c").unwrap();
         assert_eq!(
             tree,
-            Token::File(vec![Path(vec![
-                Token::Segment("\\!a".to_string()),
-                Token::Separator,
-                Token::Segment("b".to_string()),
-                Token::Separator,
-                Token::Segment("c".to_string())
-            ])])
+            Token::File {
+                span: SimpleSpan::new(0, 7),
+                children: vec![Path {
+                    span: SimpleSpan::new(0, 7),
+                    children: vec![
+                        Token::Segment {
+                            span: SimpleSpan::new(0, 3),
+                            value: "\\!a".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(3, 4)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(4, 5),
+                            value: "b".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(5, 6)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(6, 7),
+                            value: "c".to_string(),
+                        },
+                    ]
+                }]
+            }
+        );
+    }
+
+    #[test]
+    fn test_star() {
+        let tree = parser().parse("*.log").unwrap();
+        assert_eq!(
+            tree,
+            Token::File {
+                span: SimpleSpan::new(0, 5),
+                children: vec![Path {
+                    span: SimpleSpan::new(0, 5),
+                    children: vec![
+                        Token::Star {
+                            span: SimpleSpan::new(0, 1)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(1, 5),
+                            value: ".log".to_string(),
+                        },
+                    ]
+                }]
+            }
+        );
+    }
+
+    #[test]
+    fn test_double_star() {
+        let tree = parser().parse("**/build/").unwrap();
+        assert_eq!(
+            tree,
+            Token::File {
+                span: SimpleSpan::new(0, 9),
+                children: vec![Path {
+                    span: SimpleSpan::new(0, 9),
+                    children: vec![
+                        Token::DoubleStar {
+                            span: SimpleSpan::new(0, 2)
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(2, 3)
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(3, 8),
+                            value: "build".to_string(),
+                        },
+                        Token::Separator {
+                            span: SimpleSpan::new(8, 9)
+                        },
+                    ]
+                }]
+            }
+        );
+    }
+
+    #[test]
+    fn test_non_whole_component_double_star_stays_two_stars() {
+        let tree = parser().parse("a**b").unwrap();
+        let children = tree.get_children().unwrap()[0].get_children().unwrap();
+        assert!(!children.iter().any(|t| matches!(t, Token::DoubleStar { .. })));
+        assert_eq!(
+            children.iter().filter(|t| matches!(t, Token::Star { .. })).count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_any_char() {
+        let tree = parser().parse("file?.txt").unwrap();
+        let children = tree.get_children().unwrap()[0].get_children().unwrap();
+        assert!(children.iter().any(|t| matches!(t, Token::AnyChar { .. })));
+    }
+
+    #[test]
+    fn test_char_class() {
+        let tree = parser().parse("[a-z].txt").unwrap();
+        assert_eq!(
+            tree,
+            Token::File {
+                span: SimpleSpan::new(0, 9),
+                children: vec![Path {
+                    span: SimpleSpan::new(0, 9),
+                    children: vec![
+                        Token::CharClass {
+                            span: SimpleSpan::new(0, 5),
+                            negated: false,
+                            value: "a-z".to_string(),
+                        },
+                        Token::Segment {
+                            span: SimpleSpan::new(5, 9),
+                            value: ".txt".to_string(),
+                        },
+                    ]
+                }]
+            }
+        );
+    }
+
+    #[test]
+    fn test_negated_char_class() {
+        let tree = parser().parse("[!a-z]").unwrap();
+        let children = tree.get_children().unwrap()[0].get_children().unwrap();
+        assert_eq!(
+            children[0],
+            Token::CharClass {
+                span: SimpleSpan::new(0, 6),
+                negated: true,
+                value: "a-z".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_unterminated_char_class_consumes_rest_of_line() {
+        let tree = parser().parse("[a-z").unwrap();
+        let children = tree.get_children().unwrap()[0].get_children().unwrap();
+        assert_eq!(
+            children[0],
+            Token::CharClass {
+                span: SimpleSpan::new(0, 4),
+                negated: false,
+                value: "a-z".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_empty_char_class() {
+        let tree = parser().parse("[]").unwrap();
+        let children = tree.get_children().unwrap()[0].get_children().unwrap();
+        assert_eq!(
+            children[0],
+            Token::CharClass {
+                span: SimpleSpan::new(0, 2),
+                negated: false,
+                value: "".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_escaped_wildcard_stays_literal() {
+        let tree = parser().parse("\\*foo").unwrap();
+        assert_eq!(
+            tree,
+            Token::File {
+                span: SimpleSpan::new(0, 5),
+                children: vec![Path {
+                    span: SimpleSpan::new(0, 5),
+                    children: vec![Token::Segment {
+                        span: SimpleSpan::new(0, 5),
+                        value: "\\*foo".to_string(),
+                    }]
+                }]
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_all_children_filtered() {
+        let tree = parser().parse("!a/b/c\n/d/e").unwrap();
+
+        let segments = tree.get_all_children_filtered(&|t| matches!(t, Token::Segment { .. }));
+        assert_eq!(segments.len(), 5);
+
+        let negates = tree.get_all_children_filtered(&|t| matches!(t, Token::Negate { .. }));
+        assert_eq!(negates.len(), 1);
+    }
+
+    #[test]
+    fn test_trailing_backslash_stays_a_literal_backslash() {
+        let tree = parser().parse("foo\\").unwrap();
+        assert_eq!(
+            tree,
+            Token::File {
+                span: SimpleSpan::new(0, 4),
+                children: vec![Path {
+                    span: SimpleSpan::new(0, 4),
+                    children: vec![Token::Segment {
+                        span: SimpleSpan::new(0, 4),
+                        value: "foo\\".to_string(),
+                    }]
+                }]
+            }
+        );
+    }
+
+    #[test]
+    fn test_escaped_trailing_space_is_kept() {
+        let tree = parser().parse("file\\ ").unwrap();
+        assert_eq!(
+            tree,
+            Token::File {
+                span: SimpleSpan::new(0, 6),
+                children: vec![Path {
+                    span: SimpleSpan::new(0, 6),
+                    children: vec![Token::Segment {
+                        span: SimpleSpan::new(0, 6),
+                        value: "file\\ ".to_string(),
+                    }]
+                }]
+            }
         );
     }
 }
@@ -0,0 +1,141 @@
+use std::path::{Path, PathBuf};
+
+use lsp_workspace::tree::Node;
+use tower_lsp::lsp_types::{Location, Position, Range, Url};
+
+use crate::ignore_rule::IgnoreRule;
+use crate::matcher::Matcher;
+use crate::parser::Token;
+
+/// Resolves a pattern under the cursor to the concrete files it currently
+/// matches, so "go to definition" on a line like `build/**/*.o` jumps
+/// straight to what it affects instead of just the line itself. Compiles
+/// every rule in the file (not just the one under the cursor) and runs
+/// them together through `Matcher`, so the result respects the same
+/// last-match-wins precedence and ancestor-directory-exclusion invariant
+/// git itself does: a negation nested inside an already-excluded directory
+/// never resolves to anything, however precisely it targets a file there.
+pub struct PatternReferences {}
+
+impl PatternReferences {
+    /// `gitignore_dir` is the directory the `.gitignore` containing
+    /// `offset` lives in, since every rule is rooted there; `tree` is its
+    /// directory tree, read fresh by the caller via `Node::read_tree`.
+    /// Matches are sorted (so the result doesn't depend on iteration order)
+    /// and capped to `limit` so a broad pattern like `*` in a large
+    /// workspace can't flood the client with results.
+    pub fn resolve(
+        root: &Token,
+        offset: usize,
+        gitignore_dir: &Path,
+        tree: &Node,
+        limit: usize,
+    ) -> Option<Vec<Location>> {
+        let lines = root.get_children()?;
+        let cursor_line = lines.iter().find(|line| line.contains_offset(offset))?;
+
+        let rules: Vec<(&Token, IgnoreRule)> = lines
+            .iter()
+            .filter_map(|line| IgnoreRule::compile(line).map(|rule| (line, rule)))
+            .collect();
+        let cursor_index = rules
+            .iter()
+            .position(|(line, _)| std::ptr::eq(*line, cursor_line))?;
+
+        let compiled: Vec<IgnoreRule> = rules.into_iter().map(|(_, rule)| rule).collect();
+        let result = Matcher::evaluate(tree, &compiled);
+        let mut matches = result.rule_matches.into_iter().nth(cursor_index)?;
+        matches.sort();
+        matches.truncate(limit);
+
+        Some(
+            matches
+                .into_iter()
+                .filter_map(|relative| {
+                    let uri = Url::from_file_path(gitignore_dir.join(relative)).ok()?;
+                    let origin = Range::new(Position::new(0, 0), Position::new(0, 0));
+                    Some(Location::new(uri, origin))
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser::parser;
+
+    fn file(name: &str) -> (String, Node) {
+        (name.to_string(), Node::File { size: 0 })
+    }
+
+    fn dir(name: &str, children: Vec<(String, Node)>) -> (String, Node) {
+        (
+            name.to_string(),
+            Node::Dir {
+                children: children.into_iter().collect(),
+            },
+        )
+    }
+
+    fn tree(children: Vec<(String, Node)>) -> Node {
+        Node::Dir {
+            children: children.into_iter().collect(),
+        }
+    }
+
+    fn resolve(text: &str, offset: usize, dir: &Path, tree: &Node, limit: usize) -> Vec<Location> {
+        let root = parser().parse(text).unwrap();
+        PatternReferences::resolve(&root, offset, dir, tree, limit).unwrap_or_default()
+    }
+
+    #[test]
+    fn it_resolves_a_wildcard_to_the_files_it_matches() {
+        let dir = PathBuf::from("/repo");
+        let tree = tree(vec![file("debug.log"), dir("src", vec![file("main.rs")])]);
+        let locations = resolve("*.log\n", 0, &dir, &tree, 10);
+        assert_eq!(locations.len(), 1);
+        assert!(locations[0].uri.path().ends_with("debug.log"));
+    }
+
+    #[test]
+    fn it_matches_nested_files_for_an_unanchored_pattern() {
+        let dir = PathBuf::from("/repo");
+        let tree = tree(vec![dir("src", vec![file("trace.log")])]);
+        let locations = resolve("*.log\n", 0, &dir, &tree, 10);
+        assert_eq!(locations.len(), 1);
+    }
+
+    #[test]
+    fn it_caps_results_to_the_configured_limit() {
+        let dir = PathBuf::from("/repo");
+        let entries: Vec<(String, Node)> = (0..5).map(|i| file(&format!("{i}.log"))).collect();
+        let tree = tree(entries);
+        let locations = resolve("*.log\n", 0, &dir, &tree, 2);
+        assert_eq!(locations.len(), 2);
+    }
+
+    #[test]
+    fn it_returns_none_off_a_line_with_no_matchable_pattern() {
+        let dir = PathBuf::from("/repo");
+        assert!(resolve("# comment\n", 2, &dir, &tree(vec![]), 10).is_empty());
+    }
+
+    #[test]
+    fn it_does_not_resolve_a_directory_only_pattern_to_a_plain_file() {
+        let dir = PathBuf::from("/repo");
+        let tree = tree(vec![file("build")]);
+        assert!(resolve("build/\n", 0, &dir, &tree, 10).is_empty());
+    }
+
+    #[test]
+    fn it_cannot_resolve_a_negation_blocked_by_an_excluded_ancestor_directory() {
+        let dir = PathBuf::from("/repo");
+        let tree = tree(vec![dir("build", vec![file("keep")])]);
+        let locations = resolve("build/\n!build/keep\n", 8, &dir, &tree, 10);
+        assert!(locations.is_empty());
+    }
+}
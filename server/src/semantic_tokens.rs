@@ -0,0 +1,149 @@
+use tower_lsp::lsp_types::{SemanticToken, SemanticTokenType, SemanticTokensLegend};
+
+use crate::parser::Token;
+use crate::source_map::LineIndex;
+
+/// The fixed set of token kinds this crate ever highlights, and their index
+/// into `SemanticToken::token_type` as required by the LSP semantic tokens
+/// protocol.
+const LEGEND: [SemanticTokenType; 4] = [
+    SemanticTokenType::COMMENT,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::STRING,
+    SemanticTokenType::REGEXP,
+];
+
+const COMMENT: u32 = 0;
+const OPERATOR: u32 = 1;
+const STRING: u32 = 2;
+const REGEXP: u32 = 3;
+
+/// Walks the token tree and produces the delta-encoded `SemanticToken`s an
+/// editor needs to color a `.gitignore` file.
+pub struct SemanticTokensProvider {}
+
+impl SemanticTokensProvider {
+    pub fn legend() -> SemanticTokensLegend {
+        SemanticTokensLegend {
+            token_types: LEGEND.to_vec(),
+            token_modifiers: vec![],
+        }
+    }
+
+    /// Converts every leaf token with a highlightable kind into a
+    /// delta-encoded `SemanticToken`, in document order as the protocol
+    /// requires. Every token in this grammar starts and ends on the same
+    /// line, so each token's length is just its span's byte length turned
+    /// into a UTF-16 column delta.
+    pub fn tokens(text: &str, root: &Token, line_index: &LineIndex) -> Vec<SemanticToken> {
+        let leaves = root.get_all_children_filtered(&|token| Self::token_type(token).is_some());
+
+        let mut result = Vec::with_capacity(leaves.len());
+        let mut previous_line = 0;
+        let mut previous_start = 0;
+
+        for leaf in leaves {
+            // `get_all_children_filtered` already ran this same `match` to
+            // select the leaf, but it only returns `&Token`, not the mapped
+            // value, so it's recomputed here rather than threading a second
+            // return type through the generic filter.
+            let token_type = match Self::token_type(leaf) {
+                Some(token_type) => token_type,
+                None => continue,
+            };
+            let span = leaf.get_span();
+
+            let start = line_index.position(text, span.start);
+            let end = line_index.position(text, span.end);
+
+            let delta_line = start.line - previous_line;
+            let delta_start = if delta_line == 0 {
+                start.character - previous_start
+            } else {
+                start.character
+            };
+
+            result.push(SemanticToken {
+                delta_line,
+                delta_start,
+                length: end.character - start.character,
+                token_type,
+                token_modifiers_bitset: 0,
+            });
+
+            previous_line = start.line;
+            previous_start = start.character;
+        }
+
+        result
+    }
+
+    fn token_type(token: &Token) -> Option<u32> {
+        match token {
+            Token::Comment { .. } => Some(COMMENT),
+            Token::Negate { .. } | Token::Separator { .. } => Some(OPERATOR),
+            Token::Segment { .. } => Some(STRING),
+            Token::Star { .. } | Token::DoubleStar { .. } | Token::AnyChar { .. } | Token::CharClass { .. } => {
+                Some(REGEXP)
+            }
+            Token::File { .. } | Token::Path { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser::parser;
+
+    fn tokens(text: &str) -> Vec<SemanticToken> {
+        let root = parser().parse(text).unwrap();
+        let line_index = LineIndex::new(text);
+        SemanticTokensProvider::tokens(text, &root, &line_index)
+    }
+
+    #[test]
+    fn it_classifies_a_simple_path() {
+        let tokens = tokens("a/b\n");
+        let types: Vec<u32> = tokens.iter().map(|t| t.token_type).collect();
+        assert_eq!(types, vec![STRING, OPERATOR, STRING]);
+    }
+
+    #[test]
+    fn it_classifies_a_comment() {
+        let tokens = tokens("# hello\n");
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].token_type, COMMENT);
+    }
+
+    #[test]
+    fn it_classifies_a_negate_and_wildcards() {
+        let tokens = tokens("!a/*/?/[ab]\n");
+        let types: Vec<u32> = tokens.iter().map(|t| t.token_type).collect();
+        assert_eq!(
+            types,
+            vec![OPERATOR, STRING, OPERATOR, REGEXP, OPERATOR, REGEXP, OPERATOR, REGEXP]
+        );
+    }
+
+    #[test]
+    fn it_delta_encodes_positions_across_lines() {
+        let tokens = tokens("a/b\nc\n");
+        assert_eq!(tokens[0].delta_line, 0);
+        assert_eq!(tokens[0].delta_start, 0);
+        assert_eq!(tokens[0].length, 1);
+
+        assert_eq!(tokens[1].delta_line, 0);
+        assert_eq!(tokens[1].delta_start, 1);
+
+        assert_eq!(tokens[2].delta_line, 0);
+        assert_eq!(tokens[2].delta_start, 1);
+
+        // `c` is on the next line, so its delta_line is 1 and delta_start
+        // resets to an absolute column.
+        assert_eq!(tokens[3].delta_line, 1);
+        assert_eq!(tokens[3].delta_start, 0);
+    }
+}
@@ -0,0 +1,80 @@
+use tower_lsp::lsp_types::Position;
+
+/// Maps byte offsets into a document to LSP `Position`s (UTF-16 line/column),
+/// built once per document so every span→Position conversion in the server
+/// shares the same line table instead of re-scanning the text.
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> LineIndex {
+        let mut line_starts = vec![0];
+        for (i, byte) in source.bytes().enumerate() {
+            if byte == b'\n' {
+                line_starts.push(i + 1);
+            }
+        }
+        LineIndex { line_starts }
+    }
+
+    pub fn position(&self, source: &str, offset: usize) -> Position {
+        let offset = offset.min(source.len());
+        let line = self.line_of(offset);
+        let mut slice = &source[self.line_starts[line]..offset];
+        if slice.ends_with('\r') {
+            slice = &slice[..slice.len() - 1];
+        }
+
+        Position::new(line as u32, slice.encode_utf16().count() as u32)
+    }
+
+    fn line_of(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_position_on_the_first_line() {
+        let index = LineIndex::new("abc\ndef");
+        assert_eq!(index.position("abc\ndef", 2), Position::new(0, 2));
+    }
+
+    #[test]
+    fn it_finds_the_position_on_a_later_line() {
+        let index = LineIndex::new("abc\ndef\nghi");
+        assert_eq!(index.position("abc\ndef\nghi", 9), Position::new(2, 1));
+    }
+
+    #[test]
+    fn it_does_not_let_crlf_inflate_the_column() {
+        let index = LineIndex::new("abc\r\ndef");
+        assert_eq!(index.position("abc\r\ndef", 5), Position::new(1, 0));
+        assert_eq!(index.position("abc\r\ndef", 3), Position::new(0, 3));
+    }
+
+    #[test]
+    fn it_handles_an_offset_exactly_at_a_newline() {
+        let index = LineIndex::new("ab\ncd");
+        assert_eq!(index.position("ab\ncd", 2), Position::new(0, 2));
+    }
+
+    #[test]
+    fn it_handles_the_end_of_file_offset() {
+        let index = LineIndex::new("abc\ndef");
+        assert_eq!(index.position("abc\ndef", 7), Position::new(1, 3));
+    }
+
+    #[test]
+    fn it_counts_multibyte_characters_as_utf16_units() {
+        let index = LineIndex::new("héllo\nwörld");
+        assert_eq!(index.position("héllo\nwörld", 11), Position::new(1, 3));
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::Range;
+
+/// Mirrors rustc's `Applicability`: how confident we are that applying a
+/// suggestion without review keeps the file meaning the same thing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Applicability {
+    MachineApplicable,
+    MaybeIncorrect,
+}
+
+/// A suggested fix for a diagnostic, tagged with its `Applicability` and
+/// carried in `Diagnostic::data`
+/// so `Backend::code_action` can rebuild the edit without reparsing the
+/// document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub range: Range,
+    pub replacement: String,
+    pub applicability: Applicability,
+}